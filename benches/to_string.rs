@@ -0,0 +1,50 @@
+//! Compares `shallow_to_string` with `format!("{:?}", value)`, for outputs known at compile time
+//! and for ones that have to be formatted. Run with `cargo bench`.
+#![allow(dead_code)]
+// `black_box` is newer than the minimum supported version, which only applies to the library.
+#![allow(clippy::incompatible_msrv)]
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(to_string_fn, field_names)]
+enum Static {
+    Click { x: i32, y: i32 },
+    Close,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(to_string_fn)]
+enum Shown {
+    Click {
+        #[shallow_debug(show)]
+        x: i32,
+        y: i32,
+    },
+    Close,
+}
+
+const ITERATIONS: u32 = 1_000_000;
+
+/// Runs `f` [`ITERATIONS`] times and prints the average time it took.
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+    println!("{name:<24} {:>8.1} ns/iter", elapsed.as_nanos() as f64 / f64::from(ITERATIONS));
+}
+
+fn main() {
+    let value = black_box(Static::Click { x: 1, y: 2 });
+    bench("static format!", || format!("{value:?}"));
+    bench("static shallow_to_string", || value.shallow_to_string());
+
+    let value = black_box(Shown::Click { x: 1, y: 2 });
+    bench("shown format!", || format!("{value:?}"));
+    bench("shown shallow_to_string", || value.shallow_to_string());
+}
@@ -0,0 +1,918 @@
+//! Parsing of the `#[shallow_debug(...)]` helper attributes.
+
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{
+    token, Attribute, Data, Ident, LitBool, LitInt, LitStr, Meta, Path, Token, Type, WherePredicate,
+};
+
+use crate::case::Case;
+use crate::style::Style;
+use crate::template::Template;
+
+/// The name of the helper attribute that configures the derive.
+const ATTR_NAME: &str = "shallow_debug";
+
+const CONTAINER_ATTRS: &[&str] = &[
+    "rename",
+    "show_field_count",
+    "bound",
+    "builder",
+    "discriminant",
+    "transparent",
+    "prefix",
+    "field_names",
+    "full_debug",
+    "elision",
+    "name_fn",
+    "array_len",
+    "case",
+    "variant_only",
+    "with",
+    "variants_const",
+    "repr",
+    "union_field",
+    "inline_generics",
+    "impl_trait",
+    "inline",
+    "no_bounds",
+    "same_variant",
+    "format",
+    "skip_type_name",
+    "show_defaults",
+    "to_string_fn",
+    "bound_extra",
+    "collapse",
+    "name_with",
+    "json_tag",
+    "lowercase",
+    "uppercase",
+    "size",
+    "pretty_indent",
+    "variant_index",
+    "separator",
+    "rename_ident",
+    "write_fn",
+    "unit_suffix",
+];
+const VARIANT_ATTRS: &[&str] = &["skip", "rename", "rename_ident", "variant_only"];
+const FIELD_ATTRS: &[&str] = &["show", "skip"];
+const PARAM_ATTRS: &[&str] = &["bound"];
+/// The widest indentation accepted by `pretty_indent`.
+const MAX_PRETTY_INDENT: usize = 16;
+/// Keys that users reach for, which are spelled differently here, with the key to use instead.
+const SPELLED_DIFFERENTLY: &[(&str, &str)] = &[("self_name", "rename")];
+
+/// The `#[shallow_debug(...)]` attributes among `attrs`. A bare `#[shallow_debug]` sets no options,
+/// so it is accepted and left out.
+fn helper_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident(ATTR_NAME) && !matches!(attr.meta, Meta::Path(_)))
+}
+
+/// The error for a key that isn't one of the `known` attributes for the `kind` of item being
+/// parsed. Keys that are valid elsewhere get a hint about where they can be used instead.
+fn unknown_attribute(meta: &ParseNestedMeta, kind: &str, known: &[&str]) -> syn::Error {
+    let key = meta.path.to_token_stream().to_string().replace(' ', "");
+    if let Some((_, instead)) = SPELLED_DIFFERENTLY.iter().find(|(other, _)| *other == key) {
+        if known.contains(instead) {
+            return meta.error(format!("unknown attribute `{key}`, use `{instead}` instead"));
+        }
+    }
+    let elsewhere = [
+        ("containers", CONTAINER_ATTRS),
+        ("variants", VARIANT_ATTRS),
+        ("fields", FIELD_ATTRS),
+        ("type parameters", PARAM_ATTRS),
+    ];
+    let places = elsewhere.iter()
+        .filter(|(_, attrs)| attrs.contains(&key.as_str()))
+        .map(|(place, _)| *place)
+        .collect::<Vec<_>>();
+    let message = if places.is_empty() {
+        format!(
+            "unknown shallow_debug {kind} attribute `{key}`, expected one of: {}",
+            known.join(", "),
+        )
+    } else {
+        format!("`{key}` is not a {kind} attribute, it can only be used on {}", places.join(" and "))
+    };
+    meta.error(message)
+}
+
+/// Options that can be set on the type being derived.
+#[derive(Default)]
+pub(crate) struct ContainerOptions {
+    /// Print this name instead of the type's identifier.
+    pub rename: Option<String>,
+    /// Print the number of fields after the elision, as in `(..3)`.
+    pub show_field_count: bool,
+    /// Use these predicates in the `where` clause instead of the ones from the type.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// Write the output using the `Formatter::debug_*` builders.
+    pub builder: bool,
+    /// Print the explicit discriminant of unit variants, as in `Code::NotFound = 404`.
+    pub discriminant: bool,
+    /// Forward to the impl of the single field of a struct.
+    pub transparent: bool,
+    /// A path prepended to the type's name, as in `net::State`.
+    pub prefix: Option<String>,
+    /// List the names of named fields, as in `Foo { id, name, .. }`.
+    pub field_names: bool,
+    /// The cargo feature that switches to printing all fields, like `#[derive(Debug)]`.
+    pub full_debug: Option<String>,
+    /// Printed in place of the elided fields instead of `..`.
+    pub elision: Option<String>,
+    /// Generate a `shallow_name` method that returns the name of the variant.
+    pub name_fn: bool,
+    /// Print the length of a single array field, as in `Foo([..; 4])`.
+    pub array_len: bool,
+    /// Convert the names of variants to this case, as in `http_request`.
+    pub case: Option<Case>,
+    /// Print only the variant of an enum, as in `A(..)` instead of `MyEnum::A(..)`.
+    pub variant_only: bool,
+    /// A function that formats the value instead of the generated body.
+    pub with: Option<Path>,
+    /// Generate a `VARIANTS` constant with the names of all variants.
+    pub variants_const: bool,
+    /// Print the integer value of each variant as this type, as in `Opcode::Ping(1)`.
+    pub repr: Option<Type>,
+    /// The field of a union whose name is printed, as in `MyUnion { tag: .. }`.
+    pub union_field: Option<LitStr>,
+    /// Print the type arguments after the type's name, as in `MyEnum::<i32>::A(..)`.
+    pub inline_generics: bool,
+    /// The formatting trait implemented instead of the one of the derive, like `ufmt::uDebug`.
+    pub impl_trait: Option<Path>,
+    /// The `#[inline]` attribute of the generated method.
+    pub inline: Inline,
+    /// Don't add any bounds to the impl on top of the ones declared by the type.
+    pub no_bounds: bool,
+    /// Generate a `same_variant` method that compares the variants of two values.
+    pub same_variant: bool,
+    /// A template for the whole output, as in `[{name}]{fields}`.
+    pub format: Option<Template>,
+    /// Print no type name at all, as in `{ id, .. }` or `A(..)`.
+    pub skip_type_name: bool,
+    /// Print the defaults of the type parameters after the type's name, marked as defaults, as in
+    /// `Foo::</*defaults*/ T = i32>`.
+    pub show_defaults: bool,
+    /// Generate a `shallow_to_string` method that returns the `Debug` output.
+    pub to_string_fn: bool,
+    /// The fields of the variants aren't known, since `shallow_debug!` only lists whether there
+    /// are any. Not set by any attribute.
+    pub opaque_fields: bool,
+    /// Predicates added to the `where` clause, on top of the bounds it would have anyway.
+    pub bound_extra: Vec<WherePredicate>,
+    /// Look up the names of field-less variants in a table instead of matching on them.
+    pub collapse: bool,
+    /// A function that returns the name of the type, or of the variant, when the value is printed.
+    pub name_with: Option<Path>,
+    /// Print only the name, as a JSON object like `{"type":"MyEnum::A"}`.
+    pub json_tag: bool,
+    /// Print the name in lowercase, as in `myenum::a(..)`.
+    pub lowercase: bool,
+    /// Print the name in uppercase, as in `MYENUM::A(..)`.
+    pub uppercase: bool,
+    /// Print the size of the value after the elided fields, as in `MyEnum::A(.. /*24B*/)`.
+    pub size: bool,
+    /// The number of spaces the elided fields are indented by in alternate mode, 4 by default.
+    pub pretty_indent: Option<usize>,
+    /// Print the position of the variant among all of them, as in `MyEnum::C(..) [2/3]`.
+    pub variant_index: bool,
+    /// Printed between the type's name and the variant instead of `::`, as in `MyEnum.A(..)`.
+    pub separator: Option<String>,
+    /// Generate a `shallow_fmt` method that writes the `Debug` output into any `fmt::Write`.
+    pub write_fn: bool,
+    /// The text printed after the name of unit structs and variants, as in `Marker()`.
+    pub unit_suffix: Option<String>,
+}
+
+impl ContainerOptions {
+    /// The options set by `attrs` on a type with the given `data`, on top of the style set in the
+    /// environment.
+    pub fn from_attrs(attrs: &[Attribute], data: &Data) -> syn::Result<Self> {
+        ContainerOptions::from_attrs_with_style(attrs, Style::from_env()?.for_data(data))
+    }
+
+    /// The options set by `attrs`, on top of `style`. Attributes take precedence, so a style is
+    /// ignored where it conflicts with the attributes of the type.
+    pub fn from_attrs_with_style(attrs: &[Attribute], style: Style) -> syn::Result<Self> {
+        let mut options = ContainerOptions::default();
+        let (mut rename_str, mut rename_ident) = (false, false);
+        for attr in helper_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    rename_str = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename_ident") {
+                    options.rename = Some(parse_rename_ident(&meta)?);
+                    rename_ident = true;
+                    Ok(())
+                } else if meta.path.is_ident("show_field_count") {
+                    options.show_field_count = true;
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    options.bound = Some(parse_predicates(&meta, "bound")?);
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    options.builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("discriminant") {
+                    options.discriminant = true;
+                    Ok(())
+                } else if meta.path.is_ident("transparent") {
+                    options.transparent = true;
+                    Ok(())
+                } else if meta.path.is_ident("prefix") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    if lit.value().is_empty() {
+                        return Err(syn::Error::new(lit.span(), "`prefix` must not be empty"));
+                    }
+                    options.prefix = Some(lit.value());
+                    Ok(())
+                } else if meta.path.is_ident("field_names") {
+                    options.field_names = true;
+                    Ok(())
+                } else if meta.path.is_ident("full_debug") {
+                    let feature = if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<LitStr>()?.value()
+                    } else {
+                        "full-debug".to_owned()
+                    };
+                    options.full_debug = Some(feature);
+                    Ok(())
+                } else if meta.path.is_ident("elision") {
+                    options.elision = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("name_fn") {
+                    options.name_fn = true;
+                    Ok(())
+                } else if meta.path.is_ident("array_len") {
+                    options.array_len = true;
+                    Ok(())
+                } else if meta.path.is_ident("case") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    let case = Case::from_name(&lit.value()).ok_or_else(|| {
+                        let message = format!(
+                            "unknown case `{}`, expected one of: {}",
+                            lit.value(),
+                            Case::NAMES.join(", "),
+                        );
+                        syn::Error::new(lit.span(), message)
+                    })?;
+                    options.case = Some(case);
+                    Ok(())
+                } else if meta.path.is_ident("variant_only") {
+                    options.variant_only = true;
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    options.with = Some(meta.value()?.parse::<Path>()?);
+                    Ok(())
+                } else if meta.path.is_ident("variants_const") {
+                    options.variants_const = true;
+                    Ok(())
+                } else if meta.path.is_ident("repr") {
+                    options.repr = Some(meta.value()?.parse::<Type>()?);
+                    Ok(())
+                } else if meta.path.is_ident("union_field") {
+                    options.union_field = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("inline_generics") {
+                    options.inline_generics = true;
+                    Ok(())
+                } else if meta.path.is_ident("impl_trait") {
+                    options.impl_trait = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                    Ok(())
+                } else if meta.path.is_ident("inline") {
+                    let mut inline = Inline::Hint;
+                    if meta.input.peek(token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("always") {
+                                inline = Inline::Always;
+                                Ok(())
+                            } else if meta.path.is_ident("never") {
+                                inline = Inline::Never;
+                                Ok(())
+                            } else {
+                                Err(meta.error("expected `inline(always)` or `inline(never)`"))
+                            }
+                        })?;
+                    }
+                    options.inline = inline;
+                    Ok(())
+                } else if meta.path.is_ident("no_bounds") {
+                    options.no_bounds = true;
+                    Ok(())
+                } else if meta.path.is_ident("same_variant") {
+                    options.same_variant = true;
+                    Ok(())
+                } else if meta.path.is_ident("format") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    let template = Template::parse(&lit.value())
+                        .map_err(|message| syn::Error::new(lit.span(), message))?;
+                    options.format = Some(template);
+                    Ok(())
+                } else if meta.path.is_ident("skip_type_name") {
+                    options.skip_type_name = true;
+                    Ok(())
+                } else if meta.path.is_ident("show_defaults") {
+                    options.show_defaults = true;
+                    Ok(())
+                } else if meta.path.is_ident("to_string_fn") {
+                    options.to_string_fn = true;
+                    Ok(())
+                } else if meta.path.is_ident("bound_extra") {
+                    options.bound_extra.extend(parse_predicates(&meta, "bound_extra")?);
+                    Ok(())
+                } else if meta.path.is_ident("collapse") {
+                    options.collapse = true;
+                    Ok(())
+                } else if meta.path.is_ident("name_with") {
+                    options.name_with = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                    Ok(())
+                } else if meta.path.is_ident("json_tag") {
+                    options.json_tag = true;
+                    Ok(())
+                } else if meta.path.is_ident("lowercase") {
+                    options.lowercase = true;
+                    Ok(())
+                } else if meta.path.is_ident("uppercase") {
+                    options.uppercase = true;
+                    Ok(())
+                } else if meta.path.is_ident("size") {
+                    options.size = true;
+                    Ok(())
+                } else if meta.path.is_ident("pretty_indent") {
+                    let lit = meta.value()?.parse::<LitInt>()?;
+                    let indent = lit.base10_parse::<usize>()?;
+                    if indent > MAX_PRETTY_INDENT {
+                        let message =
+                            format!("`pretty_indent` must be at most {MAX_PRETTY_INDENT}");
+                        return Err(syn::Error::new(lit.span(), message));
+                    }
+                    options.pretty_indent = Some(indent);
+                    Ok(())
+                } else if meta.path.is_ident("variant_index") {
+                    options.variant_index = true;
+                    Ok(())
+                } else if meta.path.is_ident("write_fn") {
+                    options.write_fn = true;
+                    Ok(())
+                } else if meta.path.is_ident("separator") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    if lit.value().is_empty() {
+                        return Err(syn::Error::new(lit.span(), "`separator` must not be empty"));
+                    }
+                    options.separator = Some(lit.value());
+                    Ok(())
+                } else if meta.path.is_ident("unit_suffix") {
+                    options.unit_suffix = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(unknown_attribute(&meta, "container", CONTAINER_ATTRS))
+                }
+            })?;
+        }
+        let needs_write = options.show_field_count
+            || options.field_names
+            || options.elision.is_some()
+            || options.repr.is_some()
+            || options.impl_trait.is_some()
+            || options.format.is_some()
+            || options.skip_type_name
+            || options.json_tag
+            || options.size
+            || options.pretty_indent.is_some()
+            || options.unit_suffix.is_some();
+        let replaces_output = options.transparent || options.with.is_some();
+        options.builder |= style.builder && !needs_write && !replaces_output;
+        // The style is only a default, so it must never make the attributes of a type conflict.
+        let prints_type_name = options.separator.is_some()
+            || options.inline_generics
+            || options.show_defaults;
+        options.variant_only |= style.variant_only && !replaces_output && !prints_type_name;
+        let conflicts = [
+            (rename_str, rename_ident, "rename", "rename_ident"),
+            (options.builder, options.show_field_count, "builder", "show_field_count"),
+            (options.builder, options.field_names, "builder", "field_names"),
+            (options.field_names, options.show_field_count, "field_names", "show_field_count"),
+            (options.builder, options.elision.is_some(), "builder", "elision"),
+            (options.transparent, options.with.is_some(), "transparent", "with"),
+            (options.discriminant, options.repr.is_some(), "discriminant", "repr"),
+            (options.variant_only, options.inline_generics, "variant_only", "inline_generics"),
+            (options.bound.is_some(), options.no_bounds, "bound", "no_bounds"),
+            (options.no_bounds, !options.bound_extra.is_empty(), "no_bounds", "bound_extra"),
+            (options.builder, options.format.is_some(), "builder", "format"),
+            (options.repr.is_some(), options.format.is_some(), "repr", "format"),
+            (options.array_len, options.format.is_some(), "array_len", "format"),
+            (options.inline_generics, options.format.is_some(), "inline_generics", "format"),
+            (options.union_field.is_some(), options.format.is_some(), "union_field", "format"),
+            (options.builder, options.skip_type_name, "builder", "skip_type_name"),
+            (options.rename.is_some(), options.skip_type_name, "rename", "skip_type_name"),
+            (options.prefix.is_some(), options.skip_type_name, "prefix", "skip_type_name"),
+            (options.inline_generics, options.skip_type_name, "inline_generics", "skip_type_name"),
+            (options.inline_generics, options.show_defaults, "inline_generics", "show_defaults"),
+            (options.variant_only, options.show_defaults, "variant_only", "show_defaults"),
+            (options.skip_type_name, options.show_defaults, "skip_type_name", "show_defaults"),
+            (options.format.is_some(), options.show_defaults, "format", "show_defaults"),
+            (options.impl_trait.is_some(), options.to_string_fn, "impl_trait", "to_string_fn"),
+            (options.impl_trait.is_some(), options.write_fn, "impl_trait", "write_fn"),
+            (options.repr.is_some(), options.collapse, "repr", "collapse"),
+            (options.name_with.is_some(), options.format.is_some(), "name_with", "format"),
+            (options.name_with.is_some(), options.case.is_some(), "name_with", "case"),
+            (options.name_with.is_some(), options.discriminant, "name_with", "discriminant"),
+            (options.name_with.is_some(), options.collapse, "name_with", "collapse"),
+            (options.name_with.is_some(), options.name_fn, "name_with", "name_fn"),
+            (options.name_with.is_some(), options.variants_const, "name_with", "variants_const"),
+            (options.name_with.is_some(), options.inline_generics, "name_with", "inline_generics"),
+            (options.name_with.is_some(), options.show_defaults, "name_with", "show_defaults"),
+            (options.name_with.is_some(), options.skip_type_name, "name_with", "skip_type_name"),
+            (options.lowercase, options.uppercase, "lowercase", "uppercase"),
+            (options.name_with.is_some(), options.lowercase, "name_with", "lowercase"),
+            (options.name_with.is_some(), options.uppercase, "name_with", "uppercase"),
+            (options.builder, options.size, "builder", "size"),
+            (options.builder, options.pretty_indent.is_some(), "builder", "pretty_indent"),
+            (
+                options.union_field.is_some(),
+                options.pretty_indent.is_some(),
+                "union_field",
+                "pretty_indent",
+            ),
+            (options.array_len, options.size, "array_len", "size"),
+            (options.array_len, options.show_field_count, "array_len", "show_field_count"),
+            (options.repr.is_some(), options.size, "repr", "size"),
+            (options.format.is_some(), options.size, "format", "size"),
+            (options.union_field.is_some(), options.size, "union_field", "size"),
+            (options.collapse, options.size, "collapse", "size"),
+            (options.collapse, options.variant_index, "collapse", "variant_index"),
+            (options.variant_only, options.separator.is_some(), "variant_only", "separator"),
+            (options.skip_type_name, options.separator.is_some(), "skip_type_name", "separator"),
+            (options.builder, options.unit_suffix.is_some(), "builder", "unit_suffix"),
+            (options.repr.is_some(), options.unit_suffix.is_some(), "repr", "unit_suffix"),
+            (options.discriminant, options.unit_suffix.is_some(), "discriminant", "unit_suffix"),
+        ];
+        // `transparent` and `with` replace the whole output, so the options that change it would be
+        // silently ignored.
+        let output_options = [
+            (options.rename.is_some(), "rename"),
+            (options.show_field_count, "show_field_count"),
+            (options.builder, "builder"),
+            (options.discriminant, "discriminant"),
+            (options.prefix.is_some(), "prefix"),
+            (options.field_names, "field_names"),
+            (options.elision.is_some(), "elision"),
+            (options.array_len, "array_len"),
+            (options.case.is_some(), "case"),
+            (options.variant_only, "variant_only"),
+            (options.repr.is_some(), "repr"),
+            (options.union_field.is_some(), "union_field"),
+            (options.inline_generics, "inline_generics"),
+            (options.format.is_some(), "format"),
+            (options.skip_type_name, "skip_type_name"),
+            (options.show_defaults, "show_defaults"),
+            (options.collapse, "collapse"),
+            (options.name_with.is_some(), "name_with"),
+            (options.json_tag, "json_tag"),
+            (options.lowercase, "lowercase"),
+            (options.uppercase, "uppercase"),
+            (options.size, "size"),
+            (options.pretty_indent.is_some(), "pretty_indent"),
+            (options.variant_index, "variant_index"),
+            (options.unit_suffix.is_some(), "unit_suffix"),
+        ];
+        // `json_tag` prints only the name, known when the derive runs, so the options that change
+        // the fields or write part of the name at runtime would be silently ignored.
+        let tag_options = [
+            (options.show_field_count, "show_field_count"),
+            (options.builder, "builder"),
+            (options.discriminant, "discriminant"),
+            (options.field_names, "field_names"),
+            (options.elision.is_some(), "elision"),
+            (options.array_len, "array_len"),
+            (options.repr.is_some(), "repr"),
+            (options.union_field.is_some(), "union_field"),
+            (options.inline_generics, "inline_generics"),
+            (options.format.is_some(), "format"),
+            (options.skip_type_name, "skip_type_name"),
+            (options.show_defaults, "show_defaults"),
+            (options.collapse, "collapse"),
+            (options.name_with.is_some(), "name_with"),
+            (options.size, "size"),
+            (options.pretty_indent.is_some(), "pretty_indent"),
+            (options.variant_index, "variant_index"),
+            (options.separator.is_some(), "separator"),
+            (options.unit_suffix.is_some(), "unit_suffix"),
+        ];
+        let conflict = |a_name: &str, b_name: &str| syn::Error::new(
+            Span::call_site(),
+            format!("`{b_name}` can't be used together with `{a_name}`"),
+        );
+        for (a, b, a_name, b_name) in conflicts {
+            if a && b {
+                return Err(conflict(a_name, b_name));
+            }
+        }
+        let replacing = [(options.transparent, "transparent"), (options.with.is_some(), "with")];
+        for (_, a_name) in replacing.iter().filter(|(replaces, _)| *replaces) {
+            if let Some((_, b_name)) = output_options.iter().find(|(set, _)| *set) {
+                return Err(conflict(a_name, b_name));
+            }
+        }
+        if options.json_tag {
+            if let Some((_, b_name)) = tag_options.iter().find(|(set, _)| *set) {
+                return Err(conflict("json_tag", b_name));
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The name given by `rename_ident = Name`, which must be a single identifier, so that it is
+/// checked like one when the derive runs. Raw identifiers are printed without their `r#`.
+fn parse_rename_ident(meta: &ParseNestedMeta) -> syn::Result<String> {
+    let value = meta.value()?;
+    let error = |span| {
+        syn::Error::new(span, "`rename_ident` expects an identifier, as in `rename_ident = Name`")
+    };
+    let ident = value.call(Ident::parse_any).map_err(|err| error(err.span()))?;
+    if !value.is_empty() && !value.peek(Token![,]) {
+        return Err(error(value.span()));
+    }
+    Ok(ident.unraw().to_string())
+}
+
+/// The `where` predicates in the string value of the `key` attribute, as in `bound = "T: Clone"`.
+fn parse_predicates(meta: &ParseNestedMeta, key: &str) -> syn::Result<Vec<WherePredicate>> {
+    let lit = meta.value()?.parse::<LitStr>()?;
+    let predicates = lit
+        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)
+        .map_err(|err| {
+            let message = format!("invalid `{key}` predicates: {err}");
+            syn::Error::new(lit.span(), message)
+        })?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// How the generated method is inlined, as set by `inline`, `inline(always)` or `inline(never)`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Inline {
+    /// `#[inline]`, the default.
+    Hint,
+    /// `#[inline(always)]`
+    Always,
+    /// `#[inline(never)]`
+    Never,
+}
+
+impl Default for Inline {
+    fn default() -> Self {
+        Inline::Hint
+    }
+}
+
+/// Options that can be set on individual enum variants.
+#[derive(Default)]
+pub(crate) struct VariantOptions {
+    /// Print the variant as `MyEnum::..`, hiding its name.
+    pub skip: bool,
+    /// Print this name instead of the variant's identifier.
+    pub rename: Option<String>,
+    /// Whether to print only the variant, overriding the `variant_only` of the enum.
+    pub variant_only: Option<bool>,
+}
+
+impl VariantOptions {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = VariantOptions::default();
+        let (mut rename_str, mut rename_ident) = (false, false);
+        for attr in helper_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    rename_str = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename_ident") {
+                    options.rename = Some(parse_rename_ident(&meta)?);
+                    rename_ident = true;
+                    Ok(())
+                } else if meta.path.is_ident("variant_only") {
+                    let variant_only = if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<LitBool>()?.value
+                    } else {
+                        true
+                    };
+                    options.variant_only = Some(variant_only);
+                    Ok(())
+                } else {
+                    Err(unknown_attribute(&meta, "variant", VARIANT_ATTRS))
+                }
+            })?;
+        }
+        if rename_str && rename_ident {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`rename_ident` can't be used together with `rename`",
+            ));
+        }
+        Ok(options)
+    }
+}
+
+/// Options that can be set on individual fields.
+#[derive(Default)]
+pub(crate) struct FieldOptions {
+    /// Print the field's value using its `Debug` impl.
+    pub show: bool,
+    /// Don't print the field's value, printing all the other fields instead.
+    pub skip: bool,
+}
+
+impl FieldOptions {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+        for attr in helper_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("show") {
+                    options.show = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    Ok(())
+                } else {
+                    Err(unknown_attribute(&meta, "field", FIELD_ATTRS))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// Options that can be set on the type parameters of the type being derived.
+#[derive(Default)]
+pub(crate) struct ParamOptions {
+    /// Require the parameter to implement `Debug` in the impl, for shown fields of that type.
+    pub bound: bool,
+}
+
+impl ParamOptions {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = ParamOptions::default();
+        for attr in helper_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("bound") {
+                    if meta.input.peek(Token![=]) {
+                        return Err(meta.error(
+                            "`bound` takes no value on a type parameter, which is then required \
+                             to implement `Debug`",
+                        ));
+                    }
+                    options.bound = true;
+                    Ok(())
+                } else {
+                    Err(unknown_attribute(&meta, "type parameter", PARAM_ATTRS))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    const BUILDER: Style = Style { builder: true, variant_only: false };
+
+    #[test]
+    fn style_defaults() {
+        let options = ContainerOptions::from_attrs_with_style(&[], BUILDER).unwrap();
+        assert!(options.builder);
+        assert!(!options.variant_only);
+
+        let style = Style { builder: false, variant_only: true };
+        let options = ContainerOptions::from_attrs_with_style(&[], style).unwrap();
+        assert!(!options.builder);
+        assert!(options.variant_only);
+    }
+
+    #[test]
+    fn attributes_override_style() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(field_names)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, BUILDER).unwrap();
+        assert!(!options.builder);
+        assert!(options.field_names);
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(builder, show_field_count)])];
+        assert!(ContainerOptions::from_attrs_with_style(&attrs, Style::default()).is_err());
+    }
+
+    #[test]
+    fn letter_case() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(lowercase, uppercase)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`uppercase` can't be used together with `lowercase`");
+    }
+
+    #[test]
+    fn pretty_indent() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(pretty_indent = 16)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert_eq!(options.pretty_indent, Some(16));
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(pretty_indent = 17)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`pretty_indent` must be at most 16");
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(pretty_indent = -1)])];
+        assert!(ContainerOptions::from_attrs_with_style(&attrs, Style::default()).is_err());
+    }
+
+    #[test]
+    fn separator() {
+        let style = Style { builder: false, variant_only: true };
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(separator = ".")])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, style).unwrap();
+        assert_eq!(options.separator.as_deref(), Some("."));
+        assert!(!options.variant_only);
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(separator = "")])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`separator` must not be empty");
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(separator = '.')])];
+        assert!(ContainerOptions::from_attrs_with_style(&attrs, Style::default()).is_err());
+    }
+
+    #[test]
+    fn rename_ident() {
+        let attrs: [Attribute; 1] =
+            [parse_quote!(#[shallow_debug(rename_ident = r#type, builder)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert_eq!(options.rename.as_deref(), Some("type"));
+        assert!(options.builder);
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(rename_ident = Name)])];
+        let options = VariantOptions::from_attrs(&attrs).unwrap();
+        assert_eq!(options.rename.as_deref(), Some("Name"));
+
+        let expected = "`rename_ident` expects an identifier, as in `rename_ident = Name`";
+        let invalid: [Attribute; 3] = [
+            parse_quote!(#[shallow_debug(rename_ident = "Name")]),
+            parse_quote!(#[shallow_debug(rename_ident = a::b)]),
+            parse_quote!(#[shallow_debug(rename_ident = 1)]),
+        ];
+        for attr in invalid {
+            let err = VariantOptions::from_attrs(&[attr]).err().unwrap();
+            assert_eq!(err.to_string(), expected);
+        }
+
+        let attrs: [Attribute; 2] = [
+            parse_quote!(#[shallow_debug(rename = "A")]),
+            parse_quote!(#[shallow_debug(rename_ident = B)]),
+        ];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`rename_ident` can't be used together with `rename`");
+        let err = VariantOptions::from_attrs(&attrs).err().unwrap();
+        assert_eq!(err.to_string(), "`rename_ident` can't be used together with `rename`");
+    }
+
+    #[test]
+    fn unknown_attributes() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(renam = "A")])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        let expected = format!(
+            "unknown shallow_debug container attribute `renam`, expected one of: {}",
+            CONTAINER_ATTRS.join(", "),
+        );
+        assert_eq!(err.to_string(), expected);
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(show)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`show` is not a container attribute, it can only be used on fields",
+        );
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(skip)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`skip` is not a container attribute, it can only be used on variants and fields",
+        );
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(rename = "a")])];
+        let err = FieldOptions::from_attrs(&attrs).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`rename` is not a field attribute, it can only be used on containers and variants",
+        );
+    }
+
+    #[test]
+    fn spelled_differently() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(self_name = "Alias")])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "unknown attribute `self_name`, use `rename` instead");
+    }
+
+    #[test]
+    fn replaced_output() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(transparent, rename = "A")])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`rename` can't be used together with `transparent`");
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(with = f, field_names)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`field_names` can't be used together with `with`");
+
+        // The style only applies where it can.
+        let style = Style { builder: true, variant_only: true };
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(transparent)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, style).unwrap();
+        assert!(!options.builder && !options.variant_only);
+    }
+
+    #[test]
+    fn style_variant_only() {
+        let style = Style { builder: false, variant_only: true };
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(inline_generics)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, style).unwrap();
+        assert!(options.inline_generics && !options.variant_only);
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(show_defaults)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, style).unwrap();
+        assert!(options.show_defaults && !options.variant_only);
+
+        // Only enums have variants to print alone.
+        let input: syn::DeriveInput = parse_quote!(struct Foo<T>(T););
+        assert!(!style.for_data(&input.data).variant_only);
+        let input: syn::DeriveInput = parse_quote!(enum Foo { A });
+        assert!(style.for_data(&input.data).variant_only);
+    }
+
+    #[test]
+    fn bare_attribute() {
+        let attrs: [Attribute; 2] = [
+            parse_quote!(#[shallow_debug]),
+            parse_quote!(#[shallow_debug(field_names)]),
+        ];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert!(options.field_names);
+        assert!(VariantOptions::from_attrs(&attrs[..1]).is_ok());
+        assert!(FieldOptions::from_attrs(&attrs[..1]).is_ok());
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug = "field_names"])];
+        assert!(ContainerOptions::from_attrs_with_style(&attrs, Style::default()).is_err());
+    }
+
+    #[test]
+    fn array_len() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(show_field_count, array_len)])];
+        let err = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).err().unwrap();
+        assert_eq!(err.to_string(), "`show_field_count` can't be used together with `array_len`");
+    }
+
+    #[test]
+    fn full_debug() {
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(full_debug = "verbose")])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert_eq!(options.full_debug.as_deref(), Some("verbose"));
+
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(full_debug)])];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert_eq!(options.full_debug.as_deref(), Some("full-debug"));
+    }
+
+    #[test]
+    fn attribute_lists() {
+        let attrs: [Attribute; 3] = [
+            parse_quote!(#[shallow_debug()]),
+            parse_quote!(#[shallow_debug(field_names,)]),
+            parse_quote!(#[shallow_debug( rename = "A" , name_fn )]),
+        ];
+        let options = ContainerOptions::from_attrs_with_style(&attrs, Style::default()).unwrap();
+        assert!(options.field_names && options.name_fn);
+        assert_eq!(options.rename.as_deref(), Some("A"));
+
+        let attrs: [Attribute; 2] = [
+            parse_quote!(#[shallow_debug()]),
+            parse_quote!(#[shallow_debug(skip,)]),
+        ];
+        assert!(VariantOptions::from_attrs(&attrs).unwrap().skip);
+        assert!(FieldOptions::from_attrs(&attrs).unwrap().skip);
+
+        // Only one trailing comma is accepted, and only after an attribute.
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(,)])];
+        assert!(FieldOptions::from_attrs(&attrs).is_err());
+        let attrs: [Attribute; 1] = [parse_quote!(#[shallow_debug(skip,,)])];
+        assert!(FieldOptions::from_attrs(&attrs).is_err());
+    }
+}
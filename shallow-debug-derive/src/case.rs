@@ -0,0 +1,99 @@
+//! Conversion of identifiers between naming conventions, for the `case` attribute.
+
+/// The naming conventions that names can be converted to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Case {
+    /// `http_request`
+    Snake,
+    /// `http-request`
+    Kebab,
+    /// `httpRequest`
+    Camel,
+    /// `HttpRequest`
+    Pascal,
+    /// `HTTP_REQUEST`
+    ScreamingSnake,
+}
+
+impl Case {
+    /// The values accepted by `case = "..."`.
+    pub const NAMES: &'static [&'static str] =
+        &["snake", "kebab", "camel", "pascal", "screaming_snake"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake" => Some(Case::Snake),
+            "kebab" => Some(Case::Kebab),
+            "camel" => Some(Case::Camel),
+            "pascal" => Some(Case::Pascal),
+            "screaming_snake" => Some(Case::ScreamingSnake),
+            _ => None,
+        }
+    }
+
+    /// Converts `name`, which may be in any of the supported conventions, to this one.
+    pub fn apply(self, name: &str) -> String {
+        let words = words(name);
+        match self {
+            Case::Snake => join(&words, "_", str::to_lowercase),
+            Case::Kebab => join(&words, "-", str::to_lowercase),
+            Case::ScreamingSnake => join(&words, "_", str::to_uppercase),
+            Case::Pascal => join(&words, "", capitalize),
+            Case::Camel => {
+                // The first word of the pascal case name is all lowercase except for its first
+                // letter.
+                let pascal = join(&words, "", capitalize);
+                let mut chars = pascal.chars();
+                chars.next()
+                    .map(|first| first.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Splits `name` into words, at underscores and dashes and where the case changes. A run of
+/// uppercase letters is a single word, except for its last letter if a lowercase one follows, so
+/// that `HTTPRequest` is split into `HTTP` and `Request`.
+fn words(name: &str) -> Vec<&str> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if let Some(start) = start.take() {
+                words.push(&name[start..index]);
+            }
+            continue;
+        }
+        if let Some(word_start) = start {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+            let boundary = c.is_uppercase()
+                && (!prev.is_uppercase() || next.map_or(false, char::is_lowercase));
+            if boundary {
+                words.push(&name[word_start..index]);
+                start = Some(index);
+            }
+        } else {
+            start = Some(index);
+        }
+    }
+    if let Some(start) = start {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+fn join(words: &[&str], separator: &str, convert: fn(&str) -> String) -> String {
+    words.iter().map(|word| convert(word)).collect::<Vec<_>>().join(separator)
+}
+
+/// The word with its first letter in uppercase and the rest in lowercase.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
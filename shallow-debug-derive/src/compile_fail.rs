@@ -0,0 +1,608 @@
+//! Usages of the derive that must be rejected. These are doctests, since that is the only way to
+//! assert that some code fails to compile without any extra tooling.
+
+/// A `bound` that doesn't parse as a list of where predicates.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(bound = "T Clone")]
+/// struct Foo<T>(T);
+/// ```
+struct MalformedBound;
+
+/// A `bound` replaces the bounds of the impl, so they must hold for the type to be `Debug`.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(bound = "T: Copy")]
+/// struct Foo<T>(T);
+///
+/// let _ = format!("{:?}", Foo(String::new()));
+/// ```
+struct UnsatisfiedBound;
+
+/// `builder` has no way of showing the field count.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(builder, show_field_count)]
+/// struct Foo(u8);
+/// ```
+struct BuilderWithFieldCount;
+
+/// `show` can't be used on unions, since reading their fields is unsafe.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// union Foo {
+///     #[shallow_debug(show)]
+///     a: u8,
+///     b: i8,
+/// }
+/// ```
+struct ShowOnUnion;
+
+/// `transparent` needs exactly one field to forward to.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(transparent)]
+/// struct Foo(u8, u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(transparent)]
+/// enum Foo {
+///     A(u8),
+/// }
+/// ```
+struct InvalidTransparent;
+
+/// A conflicting impl is reported at the type's identifier.
+///
+/// ```compile_fail,E0119
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo;
+///
+/// impl std::fmt::Debug for Foo {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         f.write_str("Foo")
+///     }
+/// }
+/// ```
+struct ConflictingImpl;
+
+/// An empty `prefix` is most likely a mistake.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(prefix = "")]
+/// struct Foo;
+/// ```
+struct EmptyPrefix;
+
+/// Misspelled or misplaced attributes are rejected instead of being silently ignored.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(renmae = "Bar")]
+/// struct Foo;
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// enum Foo {
+///     #[shallow_debug(show)]
+///     A,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo {
+///     #[shallow_debug(rename = "b")]
+///     a: u8,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDisplay;
+/// #[derive(ShallowDisplay)]
+/// enum Foo {
+///     #[shallow_debug(hide)]
+///     A,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(self_name = "Bar")]
+/// struct Foo;
+/// ```
+struct UnknownAttribute;
+
+/// `full_debug` can't be used on unions, just like `#[derive(Debug)]`.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(full_debug)]
+/// union Foo {
+///     a: u8,
+/// }
+/// ```
+struct FullDebugUnion;
+
+/// `case` only accepts the supported naming conventions.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(case = "upper")]
+/// enum Foo {
+///     A,
+/// }
+/// ```
+struct UnknownCase;
+
+/// `variants_const` needs variants.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(variants_const)]
+/// struct Foo;
+/// ```
+struct VariantsConstStruct;
+
+/// `repr` needs an enum whose variants have no fields, so that they can be cast to an integer.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(repr = u8)]
+/// enum Foo {
+///     A,
+///     B(u8),
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(repr = u8)]
+/// struct Foo;
+/// ```
+struct InvalidRepr;
+
+/// `union_field` must name a field of a union.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(union_field = "missing")]
+/// union Foo {
+///     tag: u8,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(union_field = "tag")]
+/// struct Foo {
+///     tag: u8,
+/// }
+/// ```
+struct InvalidUnionField;
+
+/// A field can't be both shown and skipped, and only named fields can be skipped.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo {
+///     #[shallow_debug(show, skip)]
+///     a: u8,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo(#[shallow_debug(skip)] u8, u8);
+/// ```
+struct InvalidSkip;
+
+/// `impl_trait` only accepts the known traits, and the other traits don't support the options that
+/// need `core::fmt`.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(impl_trait = "my_fmt::MyDebug")]
+/// struct Foo(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// mod ufmt {
+///     pub trait uDebug {}
+/// }
+///
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(impl_trait = "ufmt::uDebug", builder)]
+/// struct Foo(u8);
+/// ```
+struct InvalidImplTrait;
+
+/// `inline` only accepts `always` and `never`.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(inline(sometimes))]
+/// struct Foo(u8);
+/// ```
+struct InvalidInline;
+
+/// `same_variant` can only be used on enums.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(same_variant)]
+/// struct Foo(u8);
+/// ```
+struct SameVariantStruct;
+
+/// The variants given to `shallow_debug_alias!` must be the variants of the aliased enum.
+///
+/// ```compile_fail
+/// enum Message {
+///     Ping,
+///     Data(u8),
+/// }
+///
+/// type Request = Message;
+///
+/// shallow_debug::shallow_debug_alias!(Request => Message { Ping });
+/// ```
+///
+/// ```compile_fail
+/// enum Message {
+///     Ping,
+/// }
+///
+/// enum Other {
+///     Ping,
+/// }
+///
+/// type Request = Message;
+///
+/// shallow_debug::shallow_debug_alias!(Request => Other { Ping });
+/// ```
+struct InvalidAlias;
+
+/// A transparent field can't directly contain the struct, since its impl would call itself.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(transparent)]
+/// struct Node(Box<Node>);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(transparent)]
+/// struct Node<'a> {
+///     next: &'a std::rc::Rc<Self>,
+/// }
+/// ```
+struct RecursiveTransparent;
+
+/// `format` only accepts the `{name}` and `{fields}` placeholders, and can't describe shown
+/// fields.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(format = "{name}{values}")]
+/// struct Foo(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(format = "[{name}]")]
+/// struct Foo {
+///     #[shallow_debug(show)]
+///     a: u8,
+/// }
+/// ```
+struct InvalidFormat;
+
+/// `skip_type_name` can't be used where nothing would be printed, or where the builders would
+/// print the name.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(skip_type_name)]
+/// struct Unit;
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(skip_type_name)]
+/// struct Foo {
+///     #[shallow_debug(show)]
+///     a: u8,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(skip_type_name, builder)]
+/// struct Foo(u8);
+/// ```
+struct InvalidSkipTypeName;
+
+/// `transparent` and `with` replace the whole output, so the attributes that change it can't be
+/// used with them.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(transparent, rename = "Id")]
+/// struct Wrapper(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// # use std::fmt;
+/// fn write_custom(_: &Event, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///     f.write_str("custom")
+/// }
+///
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(with = write_custom, variant_only)]
+/// enum Event {
+///     A,
+/// }
+/// ```
+struct ReplacedOutput;
+
+/// The `variant_only` of a variant can't change the name written before matching on the variant.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(inline_generics)]
+/// enum Foo<T> {
+///     #[shallow_debug(variant_only)]
+///     A(T),
+/// }
+/// ```
+struct InvalidVariantOnly;
+
+/// `collapse` needs the variants to be in declaration order, without fields or discriminants.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(Clone, Copy, ShallowDebug)]
+/// #[shallow_debug(collapse)]
+/// enum Foo {
+///     A = 1,
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(Clone, Copy, ShallowDebug)]
+/// #[shallow_debug(collapse)]
+/// enum Foo {
+///     A(u8),
+/// }
+/// ```
+struct InvalidCollapse;
+
+/// `json_tag` prints only the name, so nothing can be printed with it that would be ignored.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(json_tag, field_names)]
+/// struct Foo {
+///     id: u32,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(json_tag)]
+/// enum Foo {
+///     A(#[shallow_debug(show)] u32),
+/// }
+/// ```
+struct InvalidJsonTag;
+
+/// A name can't be in both letter cases.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(lowercase, uppercase)]
+/// struct Foo;
+/// ```
+struct InvalidLetterCase;
+
+/// The size is printed after the elided fields, so there must be no other text there.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(size, builder)]
+/// struct Foo(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(size)]
+/// struct Foo(#[shallow_debug(show)] u8);
+/// ```
+struct InvalidSize;
+
+/// The indentation must be a reasonable number of spaces.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(pretty_indent = 17)]
+/// struct Foo(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(pretty_indent = 2, builder)]
+/// struct Foo(u8);
+/// ```
+struct InvalidPrettyIndent;
+
+/// Only enums have variants to number.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(variant_index)]
+/// struct Foo(u8);
+/// ```
+struct InvalidVariantIndex;
+
+/// `rename_ident` only accepts an identifier.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(rename_ident = "Bar")]
+/// struct Foo;
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// enum Foo {
+///     #[shallow_debug(rename_ident = bar::Baz)]
+///     A,
+/// }
+/// ```
+struct InvalidRenameIdent;
+
+/// `bound` on a type parameter adds to the bounds of the impl, so they can't be replaced.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(no_bounds)]
+/// struct Foo<#[shallow_debug(bound)] T>(#[shallow_debug(show)] T);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo<#[shallow_debug(bound)] 'a>(&'a u8);
+/// ```
+///
+/// Without it, the shown field doesn't implement `Debug`.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Foo<T>(#[shallow_debug(show)] T);
+/// ```
+struct InvalidParamBound;
+
+/// `unit_suffix` is written with the rest of the name, so it can't be used with `builder`, or with
+/// the attributes that print something else in its place.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(unit_suffix = "()", builder)]
+/// struct Foo;
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(unit_suffix = "()", discriminant)]
+/// enum Foo {
+///     A = 1,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(unit_suffix = ())]
+/// struct Foo;
+/// ```
+struct InvalidUnitSuffix;
+
+/// The length of `array_len` replaces the elided fields, so there is no count to print.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(show_field_count, array_len)]
+/// struct Buffer([u8; 4]);
+/// ```
+struct InvalidArrayLenCount;
+
+/// `name_with` replaces the name of a struct or union, so it can't be renamed too.
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// fn name() -> &'static str {
+///     "Name"
+/// }
+///
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(rename = "Renamed", name_with = "name")]
+/// struct Foo(u8);
+/// ```
+///
+/// ```compile_fail
+/// # use shallow_debug::ShallowDebug;
+/// fn name() -> &'static str {
+///     "Name"
+/// }
+///
+/// #[derive(ShallowDebug)]
+/// #[shallow_debug(rename_ident = Renamed, name_with = "name")]
+/// union Foo {
+///     a: u8,
+/// }
+/// ```
+struct RenamedNameWith;
@@ -0,0 +1,191 @@
+//! Parsing of the input of the `shallow_debug!` macro, which lists the variants of a type that
+//! can't be annotated with `#[derive(ShallowDebug)]`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    braced, parenthesized, token, Attribute, Data, DataEnum, DeriveInput, Fields, FieldsNamed,
+    FieldsUnnamed, Generics, Ident, Path, Token, Variant, Visibility,
+};
+
+/// `#[attrs]* path::to::Type { Variant, Variant(..), Variant { .. }, ... }`
+pub(crate) struct ForeignType {
+    attrs: Vec<Attribute>,
+    path: Path,
+    brace_token: token::Brace,
+    variants: Punctuated<ForeignVariant, Token![,]>,
+}
+
+/// `#[attrs]* Alias => path::to::Type { Variant, Variant(..), Variant { .. }, ... }`
+pub(crate) struct ForeignAlias {
+    attrs: Vec<Attribute>,
+    alias: Ident,
+    target: ForeignType,
+}
+
+/// A single variant. The contents of the parenthesis or braces are ignored, since the fields are
+/// never printed anyway.
+struct ForeignVariant {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    fields: Fields,
+}
+
+impl Parse for ForeignType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let path = input.call(Path::parse_mod_style)?;
+        check_type_path(&path)?;
+        let content;
+        let brace_token = braced!(content in input);
+        let variants = content.parse_terminated(ForeignVariant::parse, Token![,])?;
+        Ok(ForeignType { attrs, path, brace_token, variants })
+    }
+}
+
+/// The path can't end in `self`, `super` or `crate`, which `parse_mod_style` accepts since they
+/// are valid in the paths of modules, or in `Self`. Its last segment is used as the identifier of
+/// the type, which would otherwise make the generated code fail with errors about types that
+/// aren't found, far from the mistake. This is mostly for paths passed through other macros.
+fn check_type_path(path: &Path) -> syn::Result<()> {
+    let ident = &path.segments.last().expect("a parsed path is never empty").ident;
+    if ["self", "super", "crate", "Self"].iter().any(|keyword| ident == keyword) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!("expected the path of a type, found `{ident}`"),
+        ));
+    }
+    Ok(())
+}
+
+impl Parse for ForeignAlias {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let alias = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let target = input.parse()?;
+        Ok(ForeignAlias { attrs, alias, target })
+    }
+}
+
+impl Parse for ForeignVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let ident = input.parse()?;
+        let fields = if input.peek(token::Paren) {
+            let content;
+            let paren_token = parenthesized!(content in input);
+            content.parse::<TokenStream>()?;
+            Fields::Unnamed(FieldsUnnamed { paren_token, unnamed: Punctuated::new() })
+        } else if input.peek(token::Brace) {
+            let content;
+            let brace_token = braced!(content in input);
+            content.parse::<TokenStream>()?;
+            Fields::Named(FieldsNamed { brace_token, named: Punctuated::new() })
+        } else {
+            Fields::Unit
+        };
+        Ok(ForeignVariant { attrs, ident, fields })
+    }
+}
+
+impl ForeignType {
+    /// The item the derive would have seen, had it been possible to put it on the type. Its
+    /// identifier is the last segment of the path.
+    pub fn to_derive_input(&self) -> DeriveInput {
+        let variants = self.variants.iter()
+            .map(|variant| Variant {
+                attrs: variant.attrs.clone(),
+                ident: variant.ident.clone(),
+                fields: variant.fields.clone(),
+                discriminant: None,
+            })
+            .collect();
+
+        DeriveInput {
+            attrs: self.attrs.clone(),
+            vis: Visibility::Inherited,
+            ident: self.ident().clone(),
+            generics: Generics::default(),
+            data: Data::Enum(DataEnum {
+                enum_token: Default::default(),
+                brace_token: self.brace_token,
+                variants,
+            }),
+        }
+    }
+
+    /// Brings the type in scope under its identifier, so that the impl can refer to it by that
+    /// name. Nothing is needed if the path is already a single identifier.
+    pub fn use_item(&self) -> TokenStream {
+        if self.path.segments.len() == 1 {
+            return TokenStream::new();
+        }
+        let path = &self.path;
+        let ident = self.ident();
+        quote!(use #path as #ident;)
+    }
+
+    fn ident(&self) -> &Ident {
+        // `parse_mod_style` never produces an empty path.
+        &self.path.segments.last().unwrap().ident
+    }
+}
+
+impl ForeignAlias {
+    /// The item the derive would have seen if the alias was the type itself, with the attributes
+    /// before the alias and before the target combined.
+    pub fn to_derive_input(&self) -> DeriveInput {
+        let mut input = self.target.to_derive_input();
+        input.attrs = self.attrs.iter().chain(&self.target.attrs).cloned().collect();
+        input.ident = self.alias.clone();
+        input
+    }
+
+    /// A function that only compiles if the alias names the target type and the listed variants
+    /// are all of its variants. The impl itself only refers to the alias, so it would otherwise
+    /// accept any enum with variants of the same names.
+    pub fn check_item(&self) -> TokenStream {
+        let alias = &self.alias;
+        let path = &self.target.path;
+        let arms = self.target.variants.iter().map(|variant| {
+            let cfgs = crate::cfg_attrs(&variant.attrs);
+            let ident = &variant.ident;
+            quote!(#(#cfgs)* #path::#ident { .. } => {})
+        });
+        quote! {
+            fn check(value: &#alias) {
+                match *value {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(tokens: TokenStream) -> String {
+        syn::parse2::<ForeignType>(tokens).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn module_paths() {
+        assert_eq!(parse_error(quote!(crate { A })), "expected the path of a type, found `crate`");
+        assert_eq!(parse_error(quote!(self { A })), "expected the path of a type, found `self`");
+        assert_eq!(
+            parse_error(quote!(super::super { A })),
+            "expected the path of a type, found `super`",
+        );
+        assert_eq!(parse_error(quote!(Self { A })), "expected the path of a type, found `Self`");
+        assert!(syn::parse2::<ForeignType>(quote!(crate::Message { A })).is_ok());
+        assert!(syn::parse2::<ForeignType>(quote!(super::Selfish { A })).is_ok());
+
+        let alias = syn::parse2::<ForeignAlias>(quote!(Alias => crate { A }));
+        assert_eq!(alias.err().unwrap().to_string(), "expected the path of a type, found `crate`");
+    }
+}
@@ -0,0 +1,322 @@
+//! Impls of formatting traits other than the standard ones, for the `impl_trait` attribute.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Fields, Path};
+
+use crate::attr::{ContainerOptions, VariantOptions};
+use crate::FmtTrait;
+
+/// The traits that `impl_trait` accepts, matched against the end of the given path, so that
+/// `core::fmt::Debug` and `std::fmt::Debug` are both the standard `Debug`.
+const KNOWN_TRAITS: &[(&[&str], Signature)] = &[
+    (&["fmt", "Debug"], Signature::Core(FmtTrait::Debug)),
+    (&["fmt", "Display"], Signature::Core(FmtTrait::Display)),
+    (&["ufmt", "uDebug"], Signature::Ufmt),
+    (&["ufmt", "uDisplay"], Signature::Ufmt),
+    (&["defmt", "Format"], Signature::Defmt),
+];
+
+/// The crates that `fmt::Debug` and `fmt::Display` can be named from. The impls of these traits
+/// always name them through `::core`, so another path would silently name another trait.
+const STD_CRATES: &[&str] = &["core", "std", "alloc"];
+
+/// The shape of the method of a formatting trait, and how text is written with its formatter.
+#[derive(Clone, Copy)]
+pub(crate) enum Signature {
+    /// `fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result`, for the traits of `core::fmt`,
+    /// which are implemented the same way as without `impl_trait`.
+    Core(FmtTrait),
+    /// `fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>`
+    Ufmt,
+    /// `fn format(&self, f: defmt::Formatter<'_>)`
+    Defmt,
+}
+
+/// The options that need the formatter of `core::fmt`, and can't be used with other traits.
+fn core_only_options(options: &ContainerOptions) -> [(bool, &'static str); 11] {
+    [
+        (options.builder, "builder"),
+        (options.repr.is_some(), "repr"),
+        (options.array_len, "array_len"),
+        (options.inline_generics, "inline_generics"),
+        (options.show_defaults, "show_defaults"),
+        (options.full_debug.is_some(), "full_debug"),
+        (options.union_field.is_some(), "union_field"),
+        (options.collapse, "collapse"),
+        (options.name_with.is_some(), "name_with"),
+        (options.size, "size"),
+        (options.pretty_indent.is_some(), "pretty_indent"),
+    ]
+}
+
+/// Implements the trait at `path` for `input`, instead of the one of the derive.
+pub(crate) fn expand(
+    input: &DeriveInput,
+    options: &ContainerOptions,
+    path: &Path,
+) -> syn::Result<TokenStream> {
+    let signature = signature(path)?;
+    if let Signature::Core(fmt_trait) = signature {
+        return crate::fmt_impls(input, fmt_trait, options);
+    }
+    if let Some((_, name)) = core_only_options(options).iter().find(|(set, _)| *set) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("`{name}` can't be used together with `impl_trait`"),
+        ));
+    }
+
+    let mut required_bounds = crate::param_bounds(input, options)?;
+    let body = if let Some(with) = &options.with {
+        quote!(#with(self, __formatter))
+    } else if options.transparent {
+        let (field, member) = crate::transparent_field(input)?;
+        if input.generics.type_params().next().is_some() {
+            let ty = &field.ty;
+            required_bounds.push(quote!(#ty: #path));
+        }
+        let method = signature.method();
+        quote!(#path::#method(&self.#member, __formatter))
+    } else {
+        signature.write_str(&module(path), &shallow_text(input, options)?)
+    };
+
+    let bounds = crate::impl_bounds(input, options, required_bounds);
+    let fmt_fn = signature.fmt_fn(&module(path), options, &body);
+    Ok(crate::impl_block(input, Some(path), &bounds, fmt_fn))
+}
+
+/// The signature of the trait at `path`, if it is one of the known traits.
+fn signature(path: &Path) -> syn::Result<Signature> {
+    let segments = path.segments.iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    KNOWN_TRAITS.iter()
+        .find(|(known, _)| {
+            segments.len() >= known.len()
+                && segments.iter().rev().zip(known.iter().rev()).all(|(a, b)| a == b)
+        })
+        .map(|(_, signature)| *signature)
+        .ok_or_else(|| {
+            let known = KNOWN_TRAITS.iter()
+                .map(|(known, _)| known.join("::"))
+                .collect::<Vec<_>>();
+            let message = format!(
+                "unsupported `impl_trait`, expected one of: {}",
+                known.join(", "),
+            );
+            syn::Error::new_spanned(path, message)
+        })
+        .and_then(|signature| match signature {
+            Signature::Core(_) if segments.len() != 3 || !STD_CRATES.contains(&&*segments[0]) => {
+                let message = format!(
+                    "`impl_trait` implements the standard `fmt::{0}`, so it must be named as \
+                     `core::fmt::{0}` or `std::fmt::{0}`",
+                    segments[segments.len() - 1],
+                );
+                Err(syn::Error::new_spanned(path, message))
+            }
+            signature => Ok(signature),
+        })
+}
+
+impl Signature {
+    /// The name of the method of the trait.
+    fn method(self) -> TokenStream {
+        match self {
+            Signature::Core(_) | Signature::Ufmt => quote!(fmt),
+            Signature::Defmt => quote!(format),
+        }
+    }
+
+    /// The generic parameters of the method, with their bounds.
+    fn generics(self, module: &TokenStream) -> (TokenStream, TokenStream) {
+        match self {
+            Signature::Core(_) | Signature::Defmt => (quote!(), quote!()),
+            Signature::Ufmt => (
+                quote!(<__W>),
+                quote!(where __W: #module uWrite + ?::core::marker::Sized,),
+            ),
+        }
+    }
+
+    /// The type of the formatter parameter.
+    fn formatter(self, module: &TokenStream) -> TokenStream {
+        match self {
+            Signature::Core(_) => quote!(&mut #module Formatter<'_>),
+            Signature::Ufmt => quote!(&mut #module Formatter<'_, __W>),
+            Signature::Defmt => quote!(#module Formatter<'_>),
+        }
+    }
+
+    /// The return type of the method, if it returns anything.
+    fn output(self, module: &TokenStream) -> TokenStream {
+        match self {
+            Signature::Core(_) => quote!(-> #module Result),
+            Signature::Ufmt => quote!(-> ::core::result::Result<(), __W::Error>),
+            Signature::Defmt => quote!(),
+        }
+    }
+
+    /// The method of the trait in `module` (the path of the trait without its last segment), with
+    /// `body` as its body.
+    pub(crate) fn fmt_fn(
+        self,
+        module: &TokenStream,
+        options: &ContainerOptions,
+        body: &TokenStream,
+    ) -> TokenStream {
+        let inline = crate::inline_attr(options.inline);
+        let method = self.method();
+        let (generics, where_clause) = self.generics(module);
+        let formatter = self.formatter(module);
+        let output = self.output(module);
+        // The formatter isn't called `f`, since a constant with that name in scope would turn the
+        // parameter into a pattern matching it.
+        quote! {
+            #inline
+            fn #method #generics(&self, __formatter: #formatter) #output #where_clause {
+                #body
+            }
+        }
+    }
+
+    /// Writes `text`, an expression of type `&str`, with the formatter of the trait in `module`.
+    fn write_str(self, module: &TokenStream, text: &TokenStream) -> TokenStream {
+        match self {
+            Signature::Core(_) | Signature::Ufmt => quote!(__formatter.write_str(#text)),
+            Signature::Defmt => quote!(#module write!(__formatter, "{=str}", #text)),
+        }
+    }
+}
+
+/// The path of the module of the trait at `path`, including the trailing `::`.
+fn module(path: &Path) -> TokenStream {
+    let leading_colon = &path.leading_colon;
+    let segments = path.segments.iter().take(path.segments.len() - 1);
+    quote!(#leading_colon #(#segments::)*)
+}
+
+/// An expression evaluating to the shallow output of `self`, which is always the default compact
+/// form, since the other formatters don't have the builders and flags of `core::fmt`.
+fn shallow_text(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<TokenStream> {
+    crate::check_skip_type_name(input, options)?;
+    crate::check_variant_index(input, options)?;
+    let name = crate::type_name(input, options);
+    let text = |name: &str, fields: &Fields| -> syn::Result<String> {
+        reject_shown(fields)?;
+        let (suffix, _) = crate::fields_suffix(fields, options, false);
+        Ok(crate::output_text(name, &suffix, options))
+    };
+    Ok(match &input.data {
+        Data::Enum(data_enum) => {
+            let arms = data_enum.variants.iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let variant_options = VariantOptions::from_attrs(&variant.attrs)?;
+                    let type_prefix =
+                        crate::type_prefix(&name, variant, &variant_options, options)?;
+                    let text = if variant_options.skip {
+                        let skipped = crate::skipped_variant(&name, &type_prefix);
+                        crate::output_text(&skipped, "", options)
+                    } else {
+                        let variant_name = crate::printed_variant_name(
+                            &type_prefix,
+                            variant,
+                            &variant_options,
+                            options,
+                        );
+                        text(&variant_name, &variant.fields)?
+                    };
+                    let index = crate::index_suffix(index, data_enum, &variant_options, options);
+                    let text = text + &index;
+                    let variant_ident = &variant.ident;
+                    let cfgs = crate::cfg_attrs(&variant.attrs);
+                    Ok(quote!(#(#cfgs)* Self::#variant_ident { .. } => #text))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            crate::match_variants(&arms)
+        }
+        Data::Struct(data_struct) => text(&name, &data_struct.fields)?.into_token_stream(),
+        Data::Union(data_union) => {
+            reject_shown(&Fields::Named(data_union.fields.clone()))?;
+            crate::output_text(&name, "", options).into_token_stream()
+        }
+    })
+}
+
+/// Field values can only be written with `core::fmt`, so no field can be shown.
+fn reject_shown(fields: &Fields) -> syn::Result<()> {
+    match crate::shown_fields(fields)?.first() {
+        Some(field) => Err(syn::Error::new_spanned(
+            field,
+            "`show` and `skip` can't be used together with `impl_trait`",
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn fmt_fn(path: Path) -> String {
+        let options = ContainerOptions::default();
+        let body = quote!(body);
+        signature(&path).unwrap().fmt_fn(&module(&path), &options, &body).to_string()
+    }
+
+    #[test]
+    fn signatures() {
+        let expected = quote! {
+            #[inline]
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                body
+            }
+        };
+        assert_eq!(fmt_fn(parse_quote!(::core::fmt::Debug)), expected.to_string());
+
+        let expected = quote! {
+            #[inline]
+            fn fmt<__W>(&self, __formatter: &mut ufmt::Formatter<'_, __W>)
+                -> ::core::result::Result<(), __W::Error>
+            where
+                __W: ufmt::uWrite + ?::core::marker::Sized,
+            {
+                body
+            }
+        };
+        assert_eq!(fmt_fn(parse_quote!(ufmt::uDisplay)), expected.to_string());
+
+        let expected = quote! {
+            #[inline]
+            fn format(&self, __formatter: my_crate::defmt::Formatter<'_>) {
+                body
+            }
+        };
+        assert_eq!(fmt_fn(parse_quote!(my_crate::defmt::Format)), expected.to_string());
+    }
+
+    #[test]
+    fn unknown_trait() {
+        let error = signature(&parse_quote!(ufmt::Debug)).err().unwrap();
+        assert!(error.to_string().starts_with("unsupported `impl_trait`"));
+    }
+
+    #[test]
+    fn standard_traits() {
+        assert!(signature(&parse_quote!(core::fmt::Debug)).is_ok());
+        assert!(signature(&parse_quote!(::std::fmt::Display)).is_ok());
+        assert!(signature(&parse_quote!(my_crate::ufmt::uDebug)).is_ok());
+        let error = signature(&parse_quote!(my_crate::fmt::Debug)).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`impl_trait` implements the standard `fmt::Debug`, so it must be named as \
+             `core::fmt::Debug` or `std::fmt::Debug`",
+        );
+        assert!(signature(&parse_quote!(fmt::Display)).is_err());
+    }
+}
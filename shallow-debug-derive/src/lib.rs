@@ -0,0 +1,2225 @@
+//! The derive macros of the [`shallow-debug`](https://docs.rs/shallow-debug) crate. This crate
+//! should not be used directly, use `shallow-debug` instead, which re-exports everything here.
+
+mod attr;
+mod case;
+#[cfg(doctest)]
+mod compile_fail;
+mod foreign;
+mod impl_trait;
+mod style;
+mod template;
+
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use syn::ext::IdentExt;
+use syn::{
+    Attribute, Data, DataEnum, DeriveInput, Expr, Field, Fields, GenericArgument, GenericParam,
+    Generics, Ident, Member, PathArguments, TraitBound, TraitBoundModifier, Type, TypeParamBound,
+    UnOp, Variant, WherePredicate,
+};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+
+use attr::{ContainerOptions, FieldOptions, Inline, ParamOptions, VariantOptions};
+use impl_trait::Signature;
+
+/// A derive macro that is able to implement `Debug` for any type, without requiring it's inner
+/// types to also implement the `Debug` trait. In order to do this, the `Debug` impl that is
+/// generated is "shallow", meaning it will only print the enum variant names, but not their
+/// internal values. You can also `#[derive(ShallowDebug)]` for structs and unions, but it will not
+/// print the field values. In general this is more useful for enums, since the variant can
+/// already tell you useful information.
+///
+/// # Attributes
+///
+/// The output can be tweaked with `#[shallow_debug(...)]` attributes. A bare `#[shallow_debug]`
+/// or an empty `#[shallow_debug()]` sets no options, so it prints the default output. The options
+/// can be split across several attributes on the same item, and a trailing comma is accepted.
+///
+/// When formatting with `{:#?}`, the elided fields are printed on their own indented line, as in
+/// `"MyEnum::A(\n    ..\n)"` or `"MyStruct {\n    ..\n}"`.
+///
+/// Variants and structs without fields are printed with just their name, like unit variants, even
+/// when written as `A()` or `A {}`, since there is nothing to elide. This is also what
+/// `#[derive(Debug)]` prints for them. With `show_field_count` they are printed with a count of
+/// zero, as in `MyEnum::A(..0)`.
+///
+/// ## Container attributes
+///
+/// - `#[shallow_debug(rename = "Name")]`: prints `Name` instead of the type's identifier. The name
+///   is printed verbatim, so it can be a path like `net::tcp::Conn`, or contain braces. Only the
+///   printed name changes, the generated code still refers to the type by its identifier, so this
+///   is also the attribute for types that are usually known by another name, like one they are
+///   re-exported as.
+/// - `#[shallow_debug(rename_ident = Name)]`: like `rename`, but the name is written as an
+///   identifier instead of a string, so anything else is rejected when the derive runs. Raw
+///   identifiers like `r#type` are printed without the `r#`. Can't be combined with `rename`.
+/// - `#[shallow_debug(name_with = "path::to::function")]`: prints the name returned by the given
+///   function instead of the type's name, for names that are only known at runtime, like translated
+///   ones. For structs and unions the function is a `fn() -> &'static str`. For enums it is a
+///   `fn(&Self) -> &'static str` that returns the name of the variant of the value, which is
+///   printed after the type's name as usual, as in `MyEnum::A(..)`, except for skipped and renamed
+///   variants. The `prefix` is still printed before the name, as in `net::Name { .. }`, but a
+///   struct or union can't also be given a name with `rename` or `rename_ident`. Unlike `with`, the
+///   rest of the output is still generated. Can't be combined with `format`, `case`,
+///   `discriminant`, `collapse`, `name_fn`, `variants_const`, `inline_generics`, `show_defaults` or
+///   `skip_type_name`.
+/// - `#[shallow_debug(variant_only)]`: prints only the name of the variant of an enum, without the
+///   type's name, as in `A(..)` instead of `MyEnum::A(..)`. Structs and unions are unaffected.
+/// - `#[shallow_debug(skip_type_name)]`: prints no type name at all, so enums are printed like with
+///   `variant_only` and structs start with their fields. Combined with `field_names`, this prints
+///   compact logs like `{ id, name, .. }` for a struct and `Login { user, .. }` for a variant.
+///   Can't be used on unions or structs without fields, which would print nothing, and can't be
+///   combined with `rename`, `prefix`, `inline_generics`, `builder`, `transparent`, `with` or
+///   shown fields of structs.
+/// - `#[shallow_debug(separator = ".")]`: prints the given text between the type's name and the
+///   name of the variant instead of `::`, as in `MyEnum.A(..)`. The `prefix` is still separated
+///   by `::`, since it is a path, and structs and unions are unaffected. Skipped variants are still
+///   printed as `MyEnum::..`, since `MyEnum...` would look like an elided name. Can't be combined
+///   with `variant_only` or `skip_type_name`, which print no type name before the variant.
+/// - `#[shallow_debug(prefix = "path")]`: prints `path::` before the type's name, for example to
+///   tell apart types with the same name from different modules.
+/// - `#[shallow_debug(case = "snake")]`: converts the names of the variants to another naming
+///   convention, one of `snake`, `kebab`, `camel`, `pascal` or `screaming_snake`, so that
+///   `HttpRequest` is printed as `http_request` with `snake`. Renamed variants are printed as
+///   given.
+/// - `#[shallow_debug(lowercase)]` and `#[shallow_debug(uppercase)]`: print the whole name in
+///   lowercase or uppercase, including the type's name and renamed variants, as in `myenum::a(..)`
+///   or `MYENUM::A(..)`. Unlike `case`, words aren't split, and the letter case applies after it,
+///   so `case = "kebab"` with `uppercase` prints `HTTP-REQUEST`. Can't be combined with each
+///   other, or with `name_with`.
+/// - `#[shallow_debug(inline_generics)]`: prints the type arguments of the value after the type's
+///   name, as given by `core::any::type_name`, as in `MyEnum::<i32, alloc::string::String>::A(..)`.
+///   Lifetimes and const parameters aren't printed. Can't be combined with `variant_only` or
+///   `transparent`.
+/// - `#[shallow_debug(show_defaults)]`: prints the defaults of the type parameters after the
+///   type's name, as given by `core::any::type_name`, as in `Foo::</*defaults*/ T = i32>(..)` for
+///   `struct Foo<T = i32>`. The type arguments of the value can't be compared with the defaults,
+///   so they are printed whatever the arguments are, marked as defaults. Parameters without a
+///   default aren't printed. Can't be combined with `inline_generics`, `variant_only`,
+///   `transparent`, `skip_type_name` or `format`.
+/// - `#[shallow_debug(format = "[{name}]{fields}")]`: a template for the whole output, in which
+///   `{name}` is replaced by the name as it would be printed, like `MyEnum::A`, and `{fields}` by
+///   the elided fields, like `(..)` or `{ id, .. }`, so the example prints `[MyEnum::A](..)`. Use
+///   `{{` and `}}` for literal braces. Other placeholders are an error. Can't be combined with
+///   `builder`, `repr`, `array_len`, `inline_generics`, `transparent`, `with`, `union_field` or
+///   shown fields.
+/// - `#[shallow_debug(json_tag)]`: prints only the name as it would be printed, in a JSON object
+///   like `{"type":"MyEnum::A"}`, for structured logs. The name is escaped to be a valid JSON
+///   string, since it can come from a `rename`. The fields are never printed, so this can't be
+///   combined with shown fields, or with the attributes that change the elided fields or write
+///   part of the name at runtime, like `field_names`, `builder`, `repr`, `format` or `name_with`.
+/// - `#[shallow_debug(size)]`: prints the size of the value in bytes, as given by
+///   `core::mem::size_of_val`, after the elided fields, as in `MyEnum::A(.. /*24B*/)`, or after
+///   the name when there are no fields, as in `MyEnum::B /*24B*/`. For debugging the layout of
+///   types, without reading any field. Can't be combined with `builder`, `array_len`, `repr`,
+///   `format`, `union_field`, `collapse`, `json_tag` or shown fields.
+/// - `#[shallow_debug(pretty_indent = 2)]`: indents the elided fields by this many spaces in
+///   alternate mode (`{:#?}`), from 0 to 16, instead of the 4 spaces of the standard pretty printed
+///   output, as in `"MyStruct {\n  ..\n}"`. Can't be combined with `builder`, `union_field` or
+///   shown fields, which are written through the builders of the formatter.
+/// - `#[shallow_debug(variant_index)]`: for enums, prints the zero-based position of the variant
+///   in the declaration, out of the number of variants, after the rest of the output, as in
+///   `MyEnum::C(..) [2/3]`. Skipped variants are printed without it. Can't be combined with
+///   `collapse` or `json_tag`, or used with `shallow_debug!`.
+/// - `#[shallow_debug(show_field_count)]`: prints the number of fields after the elision, like
+///   `MyEnum::A(..3)` or `MyStruct{..2}`.
+/// - `#[shallow_debug(elision = "...")]`: prints `...` in place of the elided fields instead of
+///   `..`, as in `MyEnum::A(...)`.
+/// - `#[shallow_debug(unit_suffix = "()")]`: prints `()` after the name of unit structs and unit
+///   variants, which print only their name otherwise, as in `Marker()` or `MyEnum::C()`, for
+///   consistency with the other shapes. Variants declared as `A()` or `A {}` aren't unit variants,
+///   and skipped variants are printed without it. Can't be combined with `builder`, `repr` or
+///   `discriminant`.
+/// - `#[shallow_debug(array_len)]`: for a struct or variant whose only field is an array, prints
+///   the length of the array, as in `Buffer([..; 4])`. The length is evaluated, so named constants
+///   and const parameters are printed as a number. Can't be combined with `show_field_count`,
+///   since the length takes the place of the count.
+/// - `#[shallow_debug(field_names)]`: lists the names of named fields, but not their values, as in
+///   `MyStruct { id, name, .. }`.
+/// - `#[shallow_debug(builder)]`: writes the output through `Formatter::debug_struct` and
+///   `Formatter::debug_tuple`, so that it is formatted exactly like the standard `Debug` output of
+///   the type with its fields elided, as in `MyStruct { .. }`. This composes well with anything
+///   that records values through `&dyn Debug`, like `tracing`'s `?field` syntax. Can't be
+///   combined with `show_field_count`, `field_names` or `elision`.
+/// - `#[shallow_debug(collapse)]`: for enums whose variants have no fields and no explicit
+///   discriminants, looks up the name of the variant in a table indexed by the value cast to an
+///   integer, instead of generating a `match` with an arm per variant. The output is the same, but
+///   the generated code doesn't grow with the number of variants, which helps the compile time of
+///   enums with hundreds of them. Since the value is cast, the impl requires the enum to be
+///   `Copy`. Can't be combined with `repr`, or used with `shallow_debug!`.
+/// - `#[shallow_debug(discriminant)]`: prints the explicit discriminant of unit variants, as in
+///   `Code::NotFound = 404`. Variants without an explicit discriminant are printed as usual.
+/// - `#[shallow_debug(repr = u8)]`: for enums whose variants have no fields, prints the integer
+///   value of each variant converted to the given type, as in `Opcode::Ping(1)`. Can't be combined
+///   with `discriminant`.
+/// - `#[shallow_debug(union_field = "tag")]`: for unions, prints the name of the given field, but
+///   not its value, as in `MyUnion { tag: .. }`.
+/// - `#[shallow_debug(transparent)]`: for structs with exactly one field, forwards to the impl of
+///   that field, without printing the struct's name at all. The field's type must implement the
+///   trait being derived, so this is the one case in which bounds are added to the impl. A field
+///   that directly contains the struct, like `Box<Self>` or `&'a Node`, is rejected since
+///   formatting it would recurse forever. Since nothing else is printed, the attributes that
+///   change the output, like `rename` or `field_names`, can't be combined with it.
+/// - `#[shallow_debug(with = path::to::function)]`: formats the value by calling the given
+///   function, of type `fn(&Self, &mut Formatter) -> fmt::Result`, instead of generating the
+///   output. This is useful for custom shallow output that still needs the impl to be generic
+///   over the type's parameters. Can't be combined with `transparent`, or with the attributes that
+///   change the generated output, like `rename` or `field_names`.
+/// - `#[shallow_debug(impl_trait = "ufmt::uDebug")]`: implements another formatting trait instead
+///   of `Debug`, with the method signature and formatter of that trait. The supported traits are
+///   `ufmt::uDebug`, `ufmt::uDisplay` and `defmt::Format`, along with `core::fmt::Debug` and
+///   `core::fmt::Display` (or their `std` paths) which are implemented as usual. The paths of the
+///   other traits are used as written, so they can point to a re-export like
+///   `my_crate::ufmt::uDebug`. Since these formatters don't support the features of `core::fmt`,
+///   the output is always the compact one, and `builder`, `repr`, `array_len`, `inline_generics`,
+///   `show_defaults`, `full_debug`, `union_field` and field attributes can't be used with them. A
+///   `with` function must have the signature of the trait's method. Only supported by
+///   `ShallowDebug`.
+/// - `#[shallow_debug(full_debug)]`: when the `full-debug` cargo feature of the crate using the
+///   derive is enabled, prints all fields like `#[derive(Debug)]` would, and the shallow output
+///   otherwise. A different feature can be used with `full_debug = "feature-name"`. The feature is
+///   one of *your* crate, not of `shallow-debug`, so it must be declared in your `Cargo.toml` (as
+///   in `[features] full-debug = []`). Otherwise it can never be enabled, and rustc warns about an
+///   unexpected `cfg` condition. Since the full output needs the fields to implement `Debug`, the
+///   impl then requires `T: Debug` for every type parameter `T`, just like `#[derive(Debug)]`.
+///   Only supported by `ShallowDebug`.
+/// - `#[shallow_debug(name_fn)]`: generates a `const fn shallow_name(&self) -> &'static str`
+///   method with the same visibility as the type, returning the name of the variant (or of the
+///   struct) as printed, without the type's name, like `"A"` for `MyEnum::A`. Skipped variants
+///   return `".."`. The method is generated by `ShallowDebug`, so with `ShallowDisplay` alone it
+///   has no effect.
+/// - `#[shallow_debug(variants_const)]`: for enums, generates a
+///   `const VARIANTS: &'static [&'static str]` with the names of all variants in declaration
+///   order, as returned by `shallow_name`. Like `name_fn`, it is generated by `ShallowDebug`.
+/// - `#[shallow_debug(same_variant)]`: for enums, generates a
+///   `fn same_variant(&self, other: &Self) -> bool` method that tells whether two values are the
+///   same variant, comparing their `core::mem::discriminant`, so the fields don't need to
+///   implement `PartialEq`. Like `name_fn`, it is generated by `ShallowDebug`.
+/// - `#[shallow_debug(to_string_fn)]`: generates a `fn shallow_to_string(&self) -> String` method
+///   with the same visibility as the type, returning the same text as `format!("{:?}", value)`,
+///   for callers that want the output directly. The method uses `alloc`, which it declares itself,
+///   so it also works in `#![no_std]` crates, on targets that have `alloc`. When the output of
+///   every value is known at compile time, that is without shown fields, `inline_generics`,
+///   `show_defaults`, `name_with`, `array_len`, `repr`, `union_field`, `size`, `builder`,
+///   `full_debug`, `transparent` or `with`, the method returns a `&'static str` instead, without
+///   allocating or formatting anything. Can't be combined with `impl_trait`. Like `name_fn`, it is
+///   generated by `ShallowDebug`.
+/// - `#[shallow_debug(write_fn)]`: generates a
+///   `fn shallow_fmt<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result` method with
+///   the same visibility as the type, which writes the same text as `write!(writer, "{:?}", value)`
+///   into any `fmt::Write`, like a `String` or a fixed buffer. When the output is known at compile
+///   time, as for `to_string_fn`, it is written with a single `write_str`, without going through
+///   a `Formatter`. Can't be combined with `impl_trait`. Like `name_fn`, it is generated by
+///   `ShallowDebug`.
+/// - `#[shallow_debug(bound = "T: Clone, U: 'a")]`: uses these predicates in the `where` clause of
+///   the generated impl instead of the bounds declared on the type. Relaxed bounds like
+///   `T: ?Sized` are kept, since they only make the impl apply to more types.
+/// - `#[shallow_debug(bound_extra = "T: 'static")]`: adds these predicates to the `where` clause of
+///   the generated impl, on top of the bounds it would have anyway, instead of replacing them like
+///   `bound`. Predicates that are already there aren't repeated. Can't be combined with
+///   `no_bounds`.
+/// - `#[shallow_debug(no_bounds)]`: doesn't add any bounds to the impl, not even the ones needed by
+///   `transparent` and `full_debug`, so that it applies whenever the generated code compiles
+///   without them, for example when a field only holds `PhantomData<T>`. The bounds declared by
+///   the type are still forwarded, since the impl isn't well formed without them. Can't be
+///   combined with `bound`.
+/// - `#[shallow_debug(inline(always))]`: the generated `fmt` method is always marked `#[inline]`,
+///   since it is usually a single write. This marks it `#[inline(always)]` instead, or
+///   `#[inline(never)]` with `inline(never)`.
+///
+/// ## Default style
+///
+/// The `SHALLOW_DEBUG_STYLE` environment variable sets options for every type in the build, as a
+/// comma separated list of:
+///
+/// - `default`: the output described above.
+/// - `builder`: as if every type had `#[shallow_debug(builder)]`, except for the ones using
+///   attributes that can't be combined with it.
+/// - `variant_only`: as if every enum had `#[shallow_debug(variant_only)]`, except for the ones
+///   using `transparent`, `with`, `separator`, `inline_generics` or `show_defaults`. Structs and
+///   unions are never affected.
+///
+/// Attributes always take precedence over the variable. Cargo doesn't know that the derive reads
+/// it, so crates that were already built may need a `cargo clean` after it changes.
+///
+/// ## Field attributes
+///
+/// - `#[shallow_debug(show)]`: prints the value of this field using its `Debug` impl, so the
+///   field's type must implement `Debug`. Types with shown fields are always written like the
+///   standard `Debug` output with the remaining fields elided, as in `MyStruct { id: 1, .. }`.
+///   Shown positional fields are followed by a single `..` when any field is elided, as in
+///   `Event::Clicked(Left, ..)`, wherever the elided fields are. Shown values are written with the
+///   same formatter, so flags like `{:#?}` apply to them too. Shown fields are always printed in
+///   declaration order. Fields disabled by `#[cfg(...)]` are removed before the derive runs, so
+///   they are neither shown nor counted as elided, and `cfg_attr` can show a field conditionally.
+///   Shown fields of recursive types, like `next: Option<Box<Node>>`, are printed as deep as the
+///   value goes, so a value that contains itself (through an `Rc` cycle, for example) can't be
+///   printed.
+/// - `#[shallow_debug(skip)]`: the opposite of `show`, for named fields. When any named field is
+///   skipped, all the other fields are shown, as in `MyStruct { id: 1, name: "a", .. }`, except
+///   for `PhantomData` fields, which are elided unless marked with `show`. Can't be combined with
+///   `show` on the same field.
+///
+/// ```rust
+/// # use shallow_debug::ShallowDebug;
+/// struct Connection;
+///
+/// #[derive(ShallowDebug)]
+/// struct Client {
+///     #[shallow_debug(show)]
+///     id: u32,
+///     connection: Connection,
+/// }
+///
+/// let client = Client { id: 7, connection: Connection };
+/// assert_eq!(format!("{client:?}"), "Client { id: 7, .. }");
+/// ```
+///
+/// ## Type parameter attributes
+///
+/// - `#[shallow_debug(bound)]`: adds `T: Debug` for this parameter `T` to the `where` clause of
+///   the generated impl, as needed by shown fields whose type uses `T`, while the other
+///   parameters stay unbounded. Can't be combined with `bound = "..."` or `no_bounds` on the
+///   type.
+///
+/// ```rust
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// struct Entry<#[shallow_debug(bound)] K, V> {
+///     #[shallow_debug(show)]
+///     key: K,
+///     value: V,
+/// }
+///
+/// struct Opaque;
+///
+/// assert_eq!(format!("{:?}", Entry { key: 1, value: Opaque }), "Entry { key: 1, .. }");
+/// ```
+///
+/// ## Variant attributes
+///
+/// - `#[shallow_debug(rename = "Name")]`: prints `Name` instead of the variant's identifier.
+/// - `#[shallow_debug(rename_ident = Name)]`: the same, with the name written as an identifier,
+///   like the container attribute.
+/// - `#[shallow_debug(skip)]`: hides the variant name, printing `MyEnum::..` regardless of the
+///   kind of fields the variant has.
+/// - `#[shallow_debug(variant_only)]`: prints only the name of this variant, as in `A(..)`, like
+///   the container attribute does for all variants. With `variant_only = false`, the variant is
+///   printed with the type's name even when the enum has `variant_only`, or when it is set by the
+///   default style. Can't be used with `inline_generics` or `show_defaults`, which print the
+///   type's name for every variant.
+///
+/// ```rust
+/// # use shallow_debug::ShallowDebug;
+/// #[derive(ShallowDebug)]
+/// enum Event {
+///     Click { x: i32, y: i32 },
+///     #[shallow_debug(rename = "HTTP Request")]
+///     HttpReq(String),
+///     #[shallow_debug(skip)]
+///     Internal(u64),
+/// }
+///
+/// assert_eq!(format!("{:?}", Event::Click { x: 1, y: 2 }), "Event::Click{..}");
+/// assert_eq!(format!("{:?}", Event::HttpReq("/".into())), "Event::HTTP Request(..)");
+/// assert_eq!(format!("{:?}", Event::Internal(42)), "Event::..");
+/// ```
+#[proc_macro_derive(ShallowDebug, attributes(shallow_debug))]
+pub fn derive_shallow_debug(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(stream as DeriveInput);
+    expand(&input, FmtTrait::Debug)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The same as [`ShallowDebug`], but implements `Display` instead of `Debug`. This is useful when
+/// the type already has a `Debug` impl, or when the shallow form is what should be shown to users.
+/// It accepts the same `#[shallow_debug(...)]` attributes.
+///
+/// ```rust
+/// # use shallow_debug::ShallowDisplay;
+/// #[derive(Debug, ShallowDisplay)]
+/// enum State {
+///     Running(u32),
+///     Stopped,
+/// }
+///
+/// assert_eq!(format!("{}", State::Running(1)), "State::Running(..)");
+/// assert_eq!(format!("{:?}", State::Running(1)), "Running(1)");
+/// ```
+#[proc_macro_derive(ShallowDisplay, attributes(shallow_debug))]
+pub fn derive_shallow_display(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(stream as DeriveInput);
+    expand(&input, FmtTrait::Display)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The same as [`ShallowDebug`], but implements `defmt::Format` instead of `Debug`, for logging
+/// with [`defmt`](https://docs.rs/defmt) on embedded targets. The output is written with
+/// `defmt::write!` as a single `{=str}` argument. This is the same as
+/// `#[shallow_debug(impl_trait = "defmt::Format")]`, so the same attributes are supported, and the
+/// crate using the derive has to depend on `defmt` itself. Only available with the `defmt`
+/// feature.
+///
+/// ```rust,ignore
+/// use shallow_debug::ShallowDefmt;
+///
+/// #[derive(ShallowDefmt)]
+/// enum State {
+///     Running(u32),
+///     Stopped,
+/// }
+///
+/// // Logs `State::Running(..)`.
+/// defmt::info!("{}", State::Running(1));
+/// ```
+#[cfg(feature = "defmt")]
+#[proc_macro_derive(ShallowDefmt, attributes(shallow_debug))]
+pub fn derive_shallow_defmt(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(stream as DeriveInput);
+    expand_defmt(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements a shallow `Debug` for a type that can't be annotated with
+/// `#[derive(ShallowDebug)]`, for example because it is generated by another macro. Since the
+/// macro can't see the type definition, its variants have to be listed. The fields of each variant
+/// are never printed, so only the kind of fields (none, `(..)` or `{ .. }`) is needed. Attributes
+/// are accepted in the same places as with the derive.
+///
+/// Like with the derive, only the last segment of the path is printed. To print the whole path,
+/// or any other one, use `#[shallow_debug(rename = "generated::Message")]` or `prefix`.
+///
+/// Note that the orphan rules still apply, so the type must be defined in the same crate.
+///
+/// ```rust
+/// mod generated {
+///     pub enum Message {
+///         Ping,
+///         Data(Vec<u8>),
+///         Close { code: u16 },
+///     }
+/// }
+///
+/// shallow_debug::shallow_debug!(generated::Message { Ping, Data(..), Close { .. } });
+///
+/// let message = generated::Message::Data(vec![1, 2, 3]);
+/// assert_eq!(format!("{message:?}"), "Message::Data(..)");
+/// ```
+#[proc_macro]
+pub fn shallow_debug(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(stream as foreign::ForeignType);
+    expand_foreign(&input.to_derive_input(), input.use_item())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The same as [`shallow_debug!`], but for a type alias of an enum, printing the alias's name
+/// instead of the enum's. Since an alias is the same type as the one it names, this implements
+/// `Debug` for the enum itself, so the enum can't also derive or implement it. This is mostly
+/// useful for aliases of specific instantiations of generic enums, like `Request = Message<u8>`,
+/// since the impl is then only for that instantiation. Aliases with generic parameters aren't
+/// supported.
+///
+/// The listed variants are checked against the enum, so it fails to compile if the alias names
+/// another type or if variants are missing.
+///
+/// ```rust
+/// pub enum Message<T> {
+///     Ping,
+///     Data(T),
+/// }
+///
+/// pub type Request = Message<u8>;
+///
+/// shallow_debug::shallow_debug_alias!(Request => Message { Ping, Data(..) });
+///
+/// assert_eq!(format!("{:?}", Request::Data(1)), "Request::Data(..)");
+/// ```
+#[proc_macro]
+pub fn shallow_debug_alias(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(stream as foreign::ForeignAlias);
+    expand_foreign(&input.to_derive_input(), input.check_item())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The formatting traits that can be derived.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FmtTrait {
+    Debug,
+    Display,
+}
+
+impl ToTokens for FmtTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            FmtTrait::Debug => quote!(::core::fmt::Debug),
+            FmtTrait::Display => quote!(::core::fmt::Display),
+        });
+    }
+}
+
+/// Implements the formatting trait `fmt_trait` for `input`.
+///
+/// The identifier of the input needs no checks: the compiler parses the item before running its
+/// derives, even when another macro generated it, so it is always the name of a struct, enum or
+/// union. Only the input of `shallow_debug!` is parsed by this crate, which rejects paths that
+/// don't name a type in `foreign`.
+fn expand(input: &DeriveInput, fmt_trait: FmtTrait) -> syn::Result<TokenStream> {
+    let options = ContainerOptions::from_attrs(&input.attrs, &input.data)?;
+    expand_with_options(input, fmt_trait, options, TokenStream::new())
+}
+
+/// Implements `Debug` for a type listed by `shallow_debug!`, whose variants have fields that
+/// aren't known. The `items` that the impls need, which the macro generates, are emitted with them.
+fn expand_foreign(input: &DeriveInput, items: TokenStream) -> syn::Result<TokenStream> {
+    let options = ContainerOptions::from_attrs(&input.attrs, &input.data)?;
+    let options = ContainerOptions { opaque_fields: true, ..options };
+    expand_with_options(input, FmtTrait::Debug, options, items)
+}
+
+/// [`expand`], with the options of the type already parsed, and with other `items` to emit along
+/// with the impls.
+fn expand_with_options(
+    input: &DeriveInput,
+    fmt_trait: FmtTrait,
+    options: ContainerOptions,
+    items: TokenStream,
+) -> syn::Result<TokenStream> {
+
+    let fmt_impls = match &options.impl_trait {
+        Some(impl_trait) if fmt_trait != FmtTrait::Debug => {
+            let message = "`impl_trait` can only be used with `ShallowDebug`";
+            return Err(syn::Error::new_spanned(impl_trait, message));
+        }
+        Some(impl_trait) => impl_trait::expand(input, &options, impl_trait)?,
+        None => fmt_impls(input, fmt_trait, &options)?,
+    };
+
+    // The inherent items are only emitted by one of the derives, so that a type can derive both.
+    // They are still built by both, to report errors regardless of which derive is used.
+    let mut inherent_items = Vec::new();
+    if options.name_fn {
+        inherent_items.push(name_fn(input, &options)?);
+    }
+    if options.variants_const {
+        inherent_items.push(variants_const(input, &options)?);
+    }
+    if options.same_variant {
+        inherent_items.push(same_variant(input)?);
+    }
+    if options.write_fn {
+        inherent_items.push(write_fn(input, &options)?);
+    }
+    let inherent_impl = if !inherent_items.is_empty() && fmt_trait == FmtTrait::Debug {
+        let bounds = generic_bounds(&input.generics);
+        Some(impl_block(input, None::<TokenStream>, &bounds, quote!(#(#inherent_items)*)))
+    } else {
+        None
+    };
+    let to_string_impl = if options.to_string_fn && fmt_trait == FmtTrait::Debug {
+        Some(to_string_impl(input, &options)?)
+    } else {
+        None
+    };
+
+    Ok(scoped(quote! {
+        #items
+        #fmt_impls
+        #inherent_impl
+        #to_string_impl
+    }))
+}
+
+/// Wraps the output of a derive or of a macro in an anonymous constant, so that the items it brings
+/// in (like `extern crate alloc`, or the `use` of `shallow_debug!`) are only visible to the impls,
+/// and never added to the user's module. The impls inside still apply to the type.
+fn scoped(items: TokenStream) -> TokenStream {
+    quote! {
+        const _: () = {
+            #items
+        };
+    }
+}
+
+/// Implements `defmt::Format` for `input`. The inherent items are left to `ShallowDebug`, so that a
+/// type can derive both.
+#[cfg(feature = "defmt")]
+fn expand_defmt(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let options = ContainerOptions::from_attrs(&input.attrs, &input.data)?;
+    if let Some(impl_trait) = &options.impl_trait {
+        let message = "`impl_trait` can't be used with `ShallowDefmt`";
+        return Err(syn::Error::new_spanned(impl_trait, message));
+    }
+    impl_trait::expand(input, &options, &syn::parse_quote!(defmt::Format)).map(scoped)
+}
+
+/// The impls of `fmt_trait` for `input`: the shallow one, and the full one if it is enabled.
+fn fmt_impls(
+    input: &DeriveInput,
+    fmt_trait: FmtTrait,
+    options: &ContainerOptions,
+) -> syn::Result<TokenStream> {
+    let (fmt_body, required_bounds) = build_fmt_body(input, fmt_trait, options)?;
+
+    let bounds = impl_bounds(input, options, required_bounds);
+
+    let shallow_impl = impl_fmt(input, fmt_trait, options, &bounds, &fmt_body);
+    Ok(match &options.full_debug {
+        None => shallow_impl,
+        Some(feature) => {
+            if fmt_trait != FmtTrait::Debug {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`full_debug` can only be used with `ShallowDebug`",
+                ));
+            }
+            // The same bounds `#[derive(Debug)]` would add.
+            let debug_bounds = input.generics.type_params()
+                .map(|param| {
+                    let ident = &param.ident;
+                    quote!(#ident: ::core::fmt::Debug)
+                });
+            let full_bounds = if options.no_bounds {
+                bounds.clone()
+            } else {
+                dedup_predicates(bounds.iter().cloned().chain(debug_bounds))
+            };
+            let full_body = full_debug_body(input)?;
+            let full_impl = impl_fmt(input, fmt_trait, options, &full_bounds, &full_body);
+
+            quote! {
+                #[cfg(feature = #feature)]
+                #full_impl
+                #[cfg(not(feature = #feature))]
+                #shallow_impl
+            }
+        }
+    })
+}
+
+/// The predicates of the `where` clause of the impl for `input`: the bounds declared by the type
+/// or the `bound` option, along with the `required` bounds of the generated code and the
+/// `bound_extra` predicates unless `no_bounds` is set.
+fn impl_bounds(
+    input: &DeriveInput,
+    options: &ContainerOptions,
+    required: Vec<TokenStream>,
+) -> Vec<TokenStream> {
+    let extra = options.bound_extra.iter().flat_map(split_predicate);
+    if options.no_bounds {
+        generic_bounds(&input.generics)
+    } else if let Some(bound) = &options.bound {
+        // Relaxed bounds only make the impl more general, so they are kept even when the bounds
+        // are replaced. Otherwise `T: ?Sized` would silently become `T: Sized`.
+        let relaxed = relaxed_bounds(&input.generics);
+        let bound = bound.iter().flat_map(split_predicate);
+        dedup_predicates(relaxed.into_iter().chain(bound).chain(extra))
+    } else {
+        dedup_predicates(generic_bounds(&input.generics).into_iter().chain(required).chain(extra))
+    }
+}
+
+/// The `T: Debug` bounds for the type parameters of `input` marked with `bound`, which are needed
+/// by the shown fields of those types. Like the other required bounds, they aren't added with
+/// `bound` or `no_bounds` on the type, so they can't be combined.
+fn param_bounds(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<Vec<TokenStream>> {
+    let mut bounds = Vec::new();
+    for param in &input.generics.params {
+        let attrs = match param {
+            GenericParam::Type(ty) => &ty.attrs,
+            GenericParam::Lifetime(lifetime) => &lifetime.attrs,
+            GenericParam::Const(cons) => &cons.attrs,
+        };
+        if !ParamOptions::from_attrs(attrs)?.bound {
+            continue;
+        }
+        let ident = match param {
+            GenericParam::Type(ty) => &ty.ident,
+            _ => return Err(syn::Error::new_spanned(
+                param,
+                "`bound` can only be used on type parameters",
+            )),
+        };
+        let replaced = if options.no_bounds {
+            Some("no_bounds")
+        } else if options.bound.is_some() {
+            Some("bound")
+        } else {
+            None
+        };
+        if let Some(replaced) = replaced {
+            let message =
+                format!("`bound` on a type parameter can't be used together with `{replaced}`");
+            return Err(syn::Error::new_spanned(param, message));
+        }
+        bounds.push(quote!(#ident: ::core::fmt::Debug));
+    }
+    Ok(bounds)
+}
+
+/// The body of the `fmt` method of the shallow impl of `fmt_trait` for `input`, along with the
+/// bounds that it needs on top of the ones declared by the type.
+fn build_fmt_body(
+    input: &DeriveInput,
+    fmt_trait: FmtTrait,
+    options: &ContainerOptions,
+) -> syn::Result<(TokenStream, Vec<TokenStream>)> {
+    let ident = &input.ident;
+    let mut name = type_name(input, options);
+    // With `inline_generics`, the name and the type arguments are written first, so the rest of
+    // the output is generated as if the type's name was empty. `show_defaults` does the same with
+    // the defaults of the type parameters, labeled by the name of the parameter.
+    let (labels, types): (Vec<_>, Vec<_>) = if options.show_defaults {
+        input.generics.type_params()
+            .filter_map(|param| {
+                let label = format!("{} = ", param.ident.unraw());
+                Some((label, param.default.as_ref()?.to_token_stream()))
+            })
+            .unzip()
+    } else if options.inline_generics {
+        input.generics.type_params()
+            .map(|param| (String::new(), param.ident.to_token_stream()))
+            .unzip()
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let write_generics = if !types.is_empty() {
+        let placeholders = labels.iter().map(|label| format!("{label}{{}}")).collect::<Vec<_>>();
+        // The defaults aren't the arguments of the value, so they are marked as such.
+        let open = if options.show_defaults { "</*defaults*/ " } else { "<" };
+        let fmt = format!("{{}}::{open}{}>", placeholders.join(", "));
+        let write_generics = quote! {
+            ::core::write!(
+                __formatter,
+                #fmt,
+                #name,
+                #(::core::any::type_name::<#types>()),*
+            )?;
+        };
+        name.clear();
+        Some(write_generics)
+    } else {
+        None
+    };
+    // Likewise, with `name_with` the name returned by the function is written first. For enums the
+    // function returns the name of the variant, so it is written in each arm instead.
+    let write_name = match &options.name_with {
+        Some(name_with) if !matches!(input.data, Data::Enum(_)) => {
+            if options.rename.is_some() {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "`name_with` replaces the name of structs and unions, so it can't be used \
+                     together with `rename` or `rename_ident`",
+                ));
+            }
+            // Only the name is replaced, so the `prefix` is still written before it.
+            let write_prefix = options.prefix.as_ref().map(|prefix| {
+                let prefix = format!("{prefix}::");
+                quote!(__formatter.write_str(#prefix)?;)
+            });
+            name.clear();
+            Some(quote!(#write_prefix __formatter.write_str(#name_with())?;))
+        }
+        _ => None,
+    };
+    // Bounds that the generated code needs, on top of the ones declared by the type.
+    let mut required_bounds = param_bounds(input, options)?;
+    if options.repr.is_some() && !matches!(input.data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(ident, "`repr` can only be used on enums"));
+    }
+    if options.union_field.is_some() && !matches!(input.data, Data::Union(_)) {
+        return Err(syn::Error::new_spanned(ident, "`union_field` can only be used on unions"));
+    }
+    if options.collapse && !matches!(input.data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(ident, "`collapse` can only be used on enums"));
+    }
+    check_variant_index(input, options)?;
+    check_skip_type_name(input, options)?;
+    let fmt_body = match &input.data {
+        _ if options.with.is_some() => {
+            let with = options.with.as_ref().unwrap();
+            quote!(#with(self, __formatter))
+        }
+        _ if options.transparent => {
+            let (field, member) = transparent_field(input)?;
+            if input.generics.type_params().next().is_some() {
+                let ty = &field.ty;
+                required_bounds.push(quote!(#ty: #fmt_trait));
+            }
+            quote!(#fmt_trait::fmt(&self.#member, __formatter))
+        }
+        // Without variants there is nothing to look up, and only the empty match is exhaustive.
+        Data::Enum(data_enum) if options.collapse && !data_enum.variants.is_empty() => {
+            required_bounds.push(quote!(Self: ::core::marker::Copy));
+            collapsed_body(data_enum, &name, options)?
+        }
+        Data::Enum(data_enum) => {
+            let variants = data_enum.variants.iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let variant_options = VariantOptions::from_attrs(&variant.attrs)?;
+                    let variant_ident = &variant.ident;
+                    let mut shown = shown_fields(&variant.fields)?;
+                    if variant_options.skip {
+                        shown.clear();
+                    }
+                    let bindings = shown.iter().map(binding).collect::<Vec<_>>();
+                    let pattern = match &variant.fields {
+                        Fields::Unnamed(_) if shown.is_empty() => {
+                            quote!(#ident::#variant_ident(..))
+                        }
+                        Fields::Named(_) | Fields::Unnamed(_) => {
+                            quote!(#ident::#variant_ident{ #(#shown: #bindings,)* .. })
+                        }
+                        Fields::Unit => quote!(#ident::#variant_ident),
+                    };
+                    let type_prefix = type_prefix(&name, variant, &variant_options, options)?;
+                    // Skipped and renamed variants have a name known when the derive runs.
+                    let name_with = options.name_with.as_ref()
+                        .filter(|_| !variant_options.skip && variant_options.rename.is_none());
+                    let variant_name = if name_with.is_some() {
+                        String::new()
+                    } else {
+                        printed_variant_name(&type_prefix, variant, &variant_options, options)
+                    };
+                    if options.repr.is_some() && !matches!(variant.fields, Fields::Unit) {
+                        return Err(syn::Error::new_spanned(
+                            variant_ident,
+                            "`repr` can only be used on enums whose variants have no fields",
+                        ));
+                    }
+                    if let Some(attr) = elided_only(options).filter(|_| !shown.is_empty()) {
+                        return Err(shown_with(&shown, attr));
+                    }
+                    let write = if variant_options.skip {
+                        let (before_size, after_size) = size_suffix(options);
+                        let skipped = skipped_variant(&name, &type_prefix);
+                        let skipped = output_text(&skipped, &before_size, options);
+                        write_sized(&skipped, &after_size, options)
+                    } else if let Some(repr) = &options.repr {
+                        write_repr(&variant_name, variant_ident, repr, options)
+                    } else if !shown.is_empty() {
+                        let values = bindings.iter().map(ToTokens::to_token_stream);
+                        let shown = shown.into_iter().zip(values);
+                        write_shown(&variant_name, &variant.fields, shown)
+                    } else {
+                        write_shallow(&variant_name, &variant.fields, options)
+                    };
+                    let write = match name_with {
+                        Some(name_with) => {
+                            let write_prefix = (!type_prefix.is_empty())
+                                .then(|| quote!(__formatter.write_str(#type_prefix)?;));
+                            quote!({
+                                #write_prefix
+                                __formatter.write_str(#name_with(self))?;
+                                #write
+                            })
+                        }
+                        None => write,
+                    };
+                    let index = index_suffix(index, data_enum, &variant_options, options);
+                    let write = if index.is_empty() {
+                        write
+                    } else {
+                        quote!({
+                            #write?;
+                            __formatter.write_str(#index)
+                        })
+                    };
+                    let cfgs = cfg_attrs(&variant.attrs);
+                    Ok(quote_spanned!(variant_ident.span()=> #(#cfgs)* #pattern => #write))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            match_variants(&variants)
+        }
+        Data::Struct(data_struct) => {
+            let shown = shown_fields(&data_struct.fields)?;
+            if let Some(attr) = elided_only(options).filter(|_| !shown.is_empty()) {
+                return Err(shown_with(&shown, attr));
+            }
+            if options.skip_type_name && !shown.is_empty() {
+                // The `debug_struct` builder would print a space before the braces.
+                return Err(syn::Error::new_spanned(
+                    &shown[0],
+                    "`show` and `skip` can't be used on structs with `skip_type_name`",
+                ));
+            }
+            if shown.is_empty() {
+                write_shallow(&name, &data_struct.fields, options)
+            } else {
+                let values = shown.iter().map(|member| quote!(&self.#member)).collect::<Vec<_>>();
+                write_shown(&name, &data_struct.fields, shown.into_iter().zip(values))
+            }
+        }
+
+        Data::Union(data_union) => {
+            let fields = Fields::Named(data_union.fields.clone());
+            if let Some(field) = shown_fields(&fields)?.first() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`show` and `skip` are not supported on unions",
+                ));
+            }
+            match &options.union_field {
+                Some(union_field) => {
+                    let exists = data_union.fields.named.iter()
+                        .any(|field| field.ident.as_ref().unwrap().unraw() == union_field.value());
+                    if !exists {
+                        let message =
+                            format!("`{}` is not a field of the union", union_field.value());
+                        return Err(syn::Error::new(union_field.span(), message));
+                    }
+                    let elision = options.elision.as_deref().unwrap_or("..");
+                    quote! {
+                        __formatter.debug_struct(#name)
+                            .field(#union_field, &::core::format_args!("{}", #elision))
+                            .finish()
+                    }
+                }
+                None => {
+                    let (before_size, after_size) = size_suffix(options);
+                    let text = output_text(&name, &before_size, options);
+                    write_sized(&text, &after_size, options)
+                }
+            }
+        }
+    };
+    let fmt_body = match write_generics.or(write_name) {
+        Some(write_generics) if options.with.is_none() => quote! {
+            #write_generics
+            #fmt_body
+        },
+        _ => fmt_body,
+    };
+    Ok((fmt_body, required_bounds))
+}
+
+/// With `collapse`, writes the name of the variant from a table indexed by the value of `self` cast
+/// to an integer, which is its position since the variants have neither fields nor explicit
+/// discriminants. The body is then the same size for any number of variants, while a `match` has
+/// an arm for each of them that the compiler has to check and lower, which adds up for enums with
+/// hundreds of variants.
+fn collapsed_body(
+    data_enum: &DataEnum,
+    name: &str,
+    options: &ContainerOptions,
+) -> syn::Result<TokenStream> {
+    if options.opaque_fields {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`collapse` can't be used with `shallow_debug!`, since the order of the variants isn't \
+             known",
+        ));
+    }
+    let names = data_enum.variants.iter()
+        .map(|variant| {
+            if !matches!(variant.fields, Fields::Unit) || variant.discriminant.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    "`collapse` can only be used on enums whose variants have no fields and no \
+                     explicit discriminants",
+                ));
+            }
+            let variant_options = VariantOptions::from_attrs(&variant.attrs)?;
+            variant_text(name, variant, &variant_options, options)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let len = names.len();
+    Ok(quote! {
+        static NAMES: [&str; #len] = [#(#names),*];
+        __formatter.write_str(NAMES[*self as usize])
+    })
+}
+
+/// The whole output for `variant` of the enum printed as `name`, with its fields elided, as in the
+/// compact output, which is known when the derive runs.
+fn variant_text(
+    name: &str,
+    variant: &Variant,
+    variant_options: &VariantOptions,
+    options: &ContainerOptions,
+) -> syn::Result<String> {
+    let type_prefix = type_prefix(name, variant, variant_options, options)?;
+    Ok(if variant_options.skip {
+        output_text(&skipped_variant(name, &type_prefix), "", options)
+    } else {
+        let variant_name = printed_variant_name(&type_prefix, variant, variant_options, options);
+        let (suffix, _) = fields_suffix(&variant.fields, options, false);
+        output_text(&variant_name, &suffix, options)
+    })
+}
+
+/// With `variant_index`, rejects the types that don't have variants in a known order: structs,
+/// unions and the enums listed by `shallow_debug!`.
+fn check_variant_index(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<()> {
+    if !options.variant_index {
+        return Ok(());
+    }
+    if !matches!(input.data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`variant_index` can only be used on enums",
+        ));
+    }
+    if options.opaque_fields {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`variant_index` can't be used with `shallow_debug!`, since the order of the variants \
+             isn't known",
+        ));
+    }
+    Ok(())
+}
+
+/// With `variant_index`, the text printed after the variant at `index` in `data_enum`, as in
+/// ` [2/3]`. Skipped variants don't print it, since it would tell which variant they are.
+fn index_suffix(
+    index: usize,
+    data_enum: &DataEnum,
+    variant_options: &VariantOptions,
+    options: &ContainerOptions,
+) -> String {
+    if options.variant_index && !variant_options.skip {
+        format!(" [{index}/{}]", data_enum.variants.len())
+    } else {
+        String::new()
+    }
+}
+
+/// With `transparent`, the single field of the struct that the impl forwards to, and how to access
+/// it.
+fn transparent_field(input: &DeriveInput) -> syn::Result<(&Field, Member)> {
+    let field = match &input.data {
+        Data::Struct(data_struct) if data_struct.fields.len() == 1 => {
+            // Only to reject invalid field attributes, all fields are shown anyway.
+            shown_fields(&data_struct.fields)?;
+            data_struct.fields.iter().next().unwrap()
+        }
+        _ => return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`transparent` requires a struct with exactly one field",
+        )),
+    };
+    if forwards_to_self(&field.ty, &input.ident) {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "`transparent` can't be used on a field that contains the struct itself, since \
+             formatting it would recurse forever",
+        ));
+    }
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(0.into()),
+    };
+    Ok((field, member))
+}
+
+/// Whether the formatting impls of `ty` forward to the ones of the type named `ident`, like
+/// `Box<Self>` or `&'a Node`. Formatting a transparent field of such a type calls the impl being
+/// generated again, with no way to stop. Other wrappers like `Option` are fine, since the
+/// recursion ends with the value.
+fn forwards_to_self(ty: &Type, ident: &Ident) -> bool {
+    match ty {
+        Type::Reference(reference) => forwards_to_self(&reference.elem, ident),
+        Type::Paren(paren) => forwards_to_self(&paren.elem, ident),
+        Type::Group(group) => forwards_to_self(&group.elem, ident),
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last().unwrap();
+            if segment.ident == "Self" || segment.ident == *ident {
+                return true;
+            }
+            let is_pointer = ["Box", "Rc", "Arc"].iter().any(|pointer| segment.ident == pointer);
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) if is_pointer => {
+                    args.args.iter().any(|arg| match arg {
+                        GenericArgument::Type(ty) => forwards_to_self(ty, ident),
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// The name the type is printed with: its `rename` or identifier, after the `prefix`, or nothing
+/// with `skip_type_name`.
+fn type_name(input: &DeriveInput, options: &ContainerOptions) -> String {
+    if options.skip_type_name {
+        return String::new();
+    }
+    let name = options.rename.clone().unwrap_or_else(|| input.ident.unraw().to_string());
+    let name = match &options.prefix {
+        Some(prefix) => format!("{prefix}::{name}"),
+        None => name,
+    };
+    letter_case(name, options)
+}
+
+/// `name` in lowercase or uppercase with the `lowercase` and `uppercase` options.
+fn letter_case(name: String, options: &ContainerOptions) -> String {
+    if options.lowercase {
+        name.to_lowercase()
+    } else if options.uppercase {
+        name.to_uppercase()
+    } else {
+        name
+    }
+}
+
+/// The type's `name` followed by `::`, or the `separator`, which is printed before `variant`,
+/// unless only the variant is printed. The `variant_only` of the variant takes precedence over the
+/// one of the enum.
+fn type_prefix(
+    name: &str,
+    variant: &Variant,
+    variant_options: &VariantOptions,
+    options: &ContainerOptions,
+) -> syn::Result<String> {
+    let variant_only = match variant_options.variant_only {
+        // The name is written before the variant is matched, or not at all.
+        Some(_) if options.inline_generics || options.show_defaults => {
+            let message = if options.inline_generics {
+                "the `variant_only` of a variant can't be used with `inline_generics`"
+            } else {
+                "the `variant_only` of a variant can't be used with `show_defaults`"
+            };
+            return Err(syn::Error::new_spanned(&variant.ident, message));
+        }
+        Some(false) if options.skip_type_name => {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                "`variant_only = false` can't be used with `skip_type_name`",
+            ));
+        }
+        Some(variant_only) => variant_only,
+        None => options.variant_only || options.skip_type_name,
+    };
+    Ok(if variant_only {
+        String::new()
+    } else {
+        format!("{name}{}", options.separator.as_deref().unwrap_or("::"))
+    })
+}
+
+/// What a skipped variant of the type printed as `name` is printed as, without its fields. Its name
+/// is replaced by `..` after the `type_prefix`, which always ends in `::` here: with a `separator`
+/// like `.`, `MyEnum...` would read as if the type's name was elided instead.
+fn skipped_variant(name: &str, type_prefix: &str) -> String {
+    if type_prefix.is_empty() {
+        "..".to_owned()
+    } else {
+        format!("{name}::..")
+    }
+}
+
+/// The name of a variant after the `type_prefix`, including its discriminant with the
+/// `discriminant` option.
+fn printed_variant_name(
+    type_prefix: &str,
+    variant: &Variant,
+    variant_options: &VariantOptions,
+    options: &ContainerOptions,
+) -> String {
+    let variant_name = variant_name(variant, variant_options, options);
+    match &variant.discriminant {
+        Some((_, discriminant))
+            if options.discriminant && matches!(variant.fields, Fields::Unit) =>
+        {
+            format!("{type_prefix}{variant_name} = {}", discriminant_text(discriminant))
+        }
+        _ => format!("{type_prefix}{variant_name}"),
+    }
+}
+
+/// The discriminant of a variant as written, as in `-1`. Only literals and their negation are
+/// written without the spaces that tokens are printed with, as in `1 << 2`.
+fn discriminant_text(discriminant: &Expr) -> String {
+    match discriminant {
+        Expr::Lit(lit) => lit.lit.to_token_stream().to_string(),
+        Expr::Unary(unary) if matches!((&unary.op, &*unary.expr), (UnOp::Neg(_), Expr::Lit(_))) => {
+            format!("-{}", discriminant_text(&unary.expr))
+        }
+        _ => discriminant.to_token_stream().to_string(),
+    }
+}
+
+/// The `shallow_name` method, which returns the name of the variant (or of the struct) without
+/// formatting anything.
+fn name_fn(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<TokenStream> {
+    let body = match &input.data {
+        Data::Enum(data_enum) => {
+            let arms = data_enum.variants.iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let variant_name = listed_variant_name(variant, options)?;
+                    let cfgs = cfg_attrs(&variant.attrs);
+                    Ok(quote!(#(#cfgs)* Self::#variant_ident { .. } => #variant_name))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            match_variants(&arms)
+        }
+        Data::Struct(_) | Data::Union(_) => {
+            let name = options.rename.clone().unwrap_or_else(|| input.ident.unraw().to_string());
+            let name = letter_case(name, options);
+            quote!(#name)
+        }
+    };
+
+    let vis = &input.vis;
+    Ok(quote! {
+        /// The name of the variant, as printed by the shallow `Debug` impl.
+        #vis const fn shallow_name(&self) -> &'static str {
+            #body
+        }
+    })
+}
+
+/// The `VARIANTS` constant, with the names of all variants of an enum in declaration order.
+fn variants_const(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<TokenStream> {
+    let data_enum = match &input.data {
+        Data::Enum(data_enum) => data_enum,
+        _ => return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`variants_const` can only be used on enums",
+        )),
+    };
+    let names = data_enum.variants.iter()
+        .map(|variant| {
+            let variant_name = listed_variant_name(variant, options)?;
+            let cfgs = cfg_attrs(&variant.attrs);
+            Ok(quote!(#(#cfgs)* #variant_name))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vis = &input.vis;
+    Ok(quote! {
+        /// The names of all variants, as printed by the shallow `Debug` impl.
+        #vis const VARIANTS: &'static [&'static str] = &[#(#names),*];
+    })
+}
+
+/// The `same_variant` method, which compares the variants of two values of an enum, without
+/// comparing their fields.
+fn same_variant(input: &DeriveInput) -> syn::Result<TokenStream> {
+    if !matches!(input.data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`same_variant` can only be used on enums",
+        ));
+    }
+
+    let vis = &input.vis;
+    Ok(quote! {
+        /// Whether `self` and `other` are the same variant, regardless of their fields.
+        #vis fn same_variant(&self, other: &Self) -> bool {
+            ::core::mem::discriminant(self) == ::core::mem::discriminant(other)
+        }
+    })
+}
+
+/// The inherent impl with the `shallow_to_string` method. It brings in `alloc` for `String`, which
+/// doesn't add a name to the user's module since the output is in an anonymous constant (see
+/// [`scoped`]). Crates would have to declare `extern crate alloc` themselves otherwise, even with
+/// `std`.
+fn to_string_impl(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<TokenStream> {
+    let vis = &input.vis;
+    let bounds = generic_bounds(&input.generics);
+    if let Some(text) = static_text(input, options)? {
+        let method = quote! {
+            /// The shallow `Debug` output of the value, which is known for every value when the
+            /// code is compiled.
+            #vis fn shallow_to_string(&self) -> &'static str {
+                #text
+            }
+        };
+        return Ok(impl_block(input, None::<TokenStream>, &bounds, method));
+    }
+    let method = quote! {
+        /// The shallow `Debug` output of the value, as a `String`.
+        #vis fn shallow_to_string(&self) -> alloc::string::String
+        where
+            Self: ::core::fmt::Debug,
+        {
+            alloc::format!("{:?}", self)
+        }
+    };
+    let impl_block = impl_block(input, None::<TokenStream>, &bounds, method);
+    Ok(quote! {
+        extern crate alloc;
+        #impl_block
+    })
+}
+
+/// The `shallow_fmt` method, which writes the output without a `Formatter` when it is known when
+/// the derive runs, and through the `Debug` impl otherwise.
+fn write_fn(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<TokenStream> {
+    let vis = &input.vis;
+    // The parameters have names that can't clash with the type's generic parameters, or with a
+    // constant in scope.
+    Ok(match static_text(input, options)? {
+        Some(text) => quote! {
+            /// Writes the shallow `Debug` output of the value into `__writer`.
+            #vis fn shallow_fmt<__W>(&self, __writer: &mut __W) -> ::core::fmt::Result
+            where
+                __W: ::core::fmt::Write + ?::core::marker::Sized,
+            {
+                __writer.write_str(#text)
+            }
+        },
+        None => quote! {
+            /// Writes the shallow `Debug` output of the value into `__writer`.
+            #vis fn shallow_fmt<__W>(&self, __writer: &mut __W) -> ::core::fmt::Result
+            where
+                __W: ::core::fmt::Write + ?::core::marker::Sized,
+                Self: ::core::fmt::Debug,
+            {
+                ::core::write!(__writer, "{:?}", self)
+            }
+        },
+    })
+}
+
+/// An expression evaluating to the compact `Debug` output of `self`, if it is known for every
+/// value when the derive runs. It isn't with the options that format part of the output, or that
+/// write it through the builders of the formatter, nor with shown fields.
+fn static_text(
+    input: &DeriveInput,
+    options: &ContainerOptions,
+) -> syn::Result<Option<TokenStream>> {
+    let formatted = options.with.is_some()
+        || options.transparent
+        || options.full_debug.is_some()
+        || options.builder
+        || options.inline_generics
+        || options.show_defaults
+        || options.name_with.is_some()
+        || options.repr.is_some()
+        || options.array_len
+        || options.union_field.is_some()
+        || options.size
+        || options.opaque_fields;
+    if formatted {
+        return Ok(None);
+    }
+    let name = type_name(input, options);
+    Ok(Some(match &input.data {
+        Data::Enum(data_enum) => {
+            let mut arms = Vec::new();
+            for (index, variant) in data_enum.variants.iter().enumerate() {
+                let variant_options = VariantOptions::from_attrs(&variant.attrs)?;
+                if !variant_options.skip && !shown_fields(&variant.fields)?.is_empty() {
+                    return Ok(None);
+                }
+                let text = variant_text(&name, variant, &variant_options, options)?
+                    + &index_suffix(index, data_enum, &variant_options, options);
+                let variant_ident = &variant.ident;
+                let cfgs = cfg_attrs(&variant.attrs);
+                arms.push(quote!(#(#cfgs)* Self::#variant_ident { .. } => #text));
+            }
+            match_variants(&arms)
+        }
+        Data::Struct(data_struct) => {
+            if !shown_fields(&data_struct.fields)?.is_empty() {
+                return Ok(None);
+            }
+            let (suffix, _) = fields_suffix(&data_struct.fields, options, false);
+            output_text(&name, &suffix, options).into_token_stream()
+        }
+        Data::Union(_) => output_text(&name, "", options).into_token_stream(),
+    }))
+}
+
+/// The name of a variant as returned by the inherent items, which is `..` for skipped variants.
+fn listed_variant_name(variant: &Variant, options: &ContainerOptions) -> syn::Result<String> {
+    let variant_options = VariantOptions::from_attrs(&variant.attrs)?;
+    if variant_options.skip {
+        Ok("..".to_owned())
+    } else {
+        Ok(variant_name(variant, &variant_options, options))
+    }
+}
+
+/// The name a variant is printed with: its `rename`, or its identifier converted to the `case` of
+/// the container, then in the letter case of `lowercase` or `uppercase`.
+fn variant_name(
+    variant: &Variant,
+    variant_options: &VariantOptions,
+    options: &ContainerOptions,
+) -> String {
+    let name = match &variant_options.rename {
+        Some(rename) => rename.clone(),
+        None => {
+            let ident = variant.ident.unraw().to_string();
+            match options.case {
+                Some(case) => case.apply(&ident),
+                None => ident,
+            }
+        }
+    };
+    letter_case(name, options)
+}
+
+/// The impl of `fmt_trait` for the type of `input`, with `bounds` in its `where` clause and
+/// `fmt_body` as the body of the `fmt` method.
+fn impl_fmt(
+    input: &DeriveInput,
+    fmt_trait: FmtTrait,
+    options: &ContainerOptions,
+    bounds: &[TokenStream],
+    fmt_body: &TokenStream,
+) -> TokenStream {
+    let fmt_fn = Signature::Core(fmt_trait).fmt_fn(&quote!(::core::fmt::), options, fmt_body);
+    impl_block(input, Some(fmt_trait), bounds, fmt_fn)
+}
+
+/// The `#[inline]` attribute of the generated method. It is an inline hint by default, since the
+/// shallow output is usually a single write.
+fn inline_attr(inline: Inline) -> TokenStream {
+    match inline {
+        Inline::Hint => quote!(#[inline]),
+        Inline::Always => quote!(#[inline(always)]),
+        Inline::Never => quote!(#[inline(never)]),
+    }
+}
+
+/// An impl block for the type of `input` containing `items`, with `bounds` in its `where` clause.
+/// It is an impl of `of_trait` if there is one, and an inherent impl otherwise.
+fn impl_block(
+    input: &DeriveInput,
+    of_trait: Option<impl ToTokens>,
+    bounds: &[TokenStream],
+    items: TokenStream,
+) -> TokenStream {
+    let ident = &input.ident;
+    // `#[automatically_derived]` is only allowed on trait impls.
+    let automatically_derived = of_trait.as_ref().map(|_| quote!(#[automatically_derived]));
+    let impl_header = build_impl_header(input, of_trait, bounds);
+
+    // The impl item is spanned to the type's identifier, so that errors about the impl (like
+    // conflicting impls) point at the type rather than at the derive. Deprecation warnings are
+    // allowed since the generated code names deprecated types and variants (in the impl header
+    // and in match arms) without the user having written those uses.
+    quote_spanned! {ident.span()=>
+        #automatically_derived
+        #[allow(unused_qualifications, deprecated)]
+        #impl_header {
+            #items
+        }
+    }
+}
+
+/// The `impl<...> Trait for Type<...> where ...` part of an impl for the type of `input`, or
+/// `impl<...> Type<...> where ...` for an inherent impl.
+fn build_impl_header(
+    input: &DeriveInput,
+    of_trait: Option<impl ToTokens>,
+    bounds: &[TokenStream],
+) -> TokenStream {
+    let ident = &input.ident;
+
+    // The generic parameters as they are declared in `impl<...>`, without any defaults.
+    let impl_params = input.generics.params.iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(lifetime) => lifetime.lifetime.to_token_stream(),
+            GenericParam::Type(ty) => ty.ident.to_token_stream(),
+            GenericParam::Const(cons) => {
+                let ident = &cons.ident;
+                let ty = &cons.ty;
+                quote!(const #ident: #ty)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The generic arguments as they are used to name the type, `#ident<...>`.
+    let ty_args = input.generics.params.iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(lifetime) => lifetime.lifetime.to_token_stream(),
+            GenericParam::Type(ty) => ty.ident.to_token_stream(),
+            GenericParam::Const(cons) => cons.ident.to_token_stream(),
+        })
+        .collect::<Vec<_>>();
+
+    // The `where ...` part, only present if there is at least one predicate
+    let where_clause = if bounds.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            where
+                #(#bounds),*
+        }
+    };
+
+    let trait_for = of_trait.map(|of_trait| quote!(#of_trait for));
+
+    // The `impl Debug for <type> where ...` part
+    // The impl is emitted right after the item, in the same scope, so the bare identifier names the
+    // item even when it is local to a function or shadows another one.
+    // Even without generics there may be predicates, like `where u8: Copy`, and they are forwarded
+    // just the same. Predicates on `Self` need no rewriting either, since in the impl `Self` is the
+    // same type as in the declaration.
+    if impl_params.is_empty() {
+        quote_spanned! {ident.span()=>
+            impl #trait_for #ident
+            #where_clause
+        }
+    } else {
+        quote_spanned! {ident.span()=>
+            impl<#(#impl_params),*> #trait_for #ident<#(#ty_args),*>
+            #where_clause
+        }
+    }
+}
+
+/// A body for `Debug::fmt` that prints all fields, like `#[derive(Debug)]` does.
+fn full_debug_body(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    match &input.data {
+        Data::Struct(data_struct) => {
+            let members = data_struct.fields.members();
+            let values = members.map(|member| quote!(&self.#member));
+            Ok(write_all_fields(&ident.unraw().to_string(), &data_struct.fields, values))
+        }
+        Data::Enum(data_enum) => {
+            let arms = data_enum.variants.iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let members = variant.fields.members().collect::<Vec<_>>();
+                    let bindings = members.iter().map(binding).collect::<Vec<_>>();
+                    let name = variant_ident.unraw().to_string();
+                    let write = write_all_fields(&name, &variant.fields, bindings.iter());
+                    let cfgs = cfg_attrs(&variant.attrs);
+                    quote!(#(#cfgs)* Self::#variant_ident { #(#members: #bindings,)* } => #write)
+                });
+            Ok(match_variants(&arms.collect::<Vec<_>>()))
+        }
+        Data::Union(_) => Err(syn::Error::new_spanned(
+            ident,
+            "`full_debug` is not supported on unions",
+        )),
+    }
+}
+
+/// A `match` on `self` with the `arms` for the variants of an enum. Matching on the `&Self` binds
+/// the fields by shared reference, so fields that aren't `Copy` or that hold `&mut` references are
+/// never moved out of or reborrowed mutably. Without variants `self` is dereferenced, since only
+/// then is the empty match exhaustive.
+fn match_variants(arms: &[TokenStream]) -> TokenStream {
+    if arms.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(#arms,)*
+            }
+        }
+    }
+}
+
+/// The `#[cfg(...)]` attributes of a variant, to be put on each match arm for it. The compiler
+/// strips disabled variants before running derives, but the variants listed in `shallow_debug!`
+/// are passed as they are written. Other attributes, including `#[cfg_attr(...)]`, aren't
+/// forwarded since most of them aren't valid on match arms.
+fn cfg_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("cfg"))
+}
+
+/// Writes `name` and all of the `fields` with their `Debug` impl. Each field is paired with an
+/// expression that evaluates to a reference to its value.
+fn write_all_fields(
+    name: &str,
+    fields: &Fields,
+    values: impl Iterator<Item = impl ToTokens>,
+) -> TokenStream {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter()
+                .map(|field| field.ident.as_ref().unwrap().unraw().to_string());
+            quote!(__formatter.debug_struct(#name)#(.field(#names, #values))*.finish())
+        }
+        Fields::Unnamed(_) => quote!(__formatter.debug_tuple(#name)#(.field(#values))*.finish()),
+        Fields::Unit => quote!(__formatter.write_str(#name)),
+    }
+}
+
+/// The fields that are printed with their `Debug` impl, in declaration order. These are the ones
+/// marked with `#[shallow_debug(show)]` or, if any named field is marked with
+/// `#[shallow_debug(skip)]`, all the fields that aren't skipped or `PhantomData`.
+fn shown_fields(fields: &Fields) -> syn::Result<Vec<Member>> {
+    let options = fields.iter()
+        .map(|field| FieldOptions::from_attrs(&field.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let any_skipped = options.iter().any(|options| options.skip);
+    let mut shown = Vec::new();
+    for ((field, options), member) in fields.iter().zip(&options).zip(fields.members()) {
+        if options.show && options.skip {
+            return Err(syn::Error::new_spanned(field, "`skip` can't be used together with `show`"));
+        }
+        if options.skip && field.ident.is_none() {
+            return Err(syn::Error::new_spanned(field, "`skip` is only supported on named fields"));
+        }
+        // `PhantomData` says nothing about the value, so it is only shown when asked explicitly.
+        let is_shown = if any_skipped {
+            !options.skip && (options.show || !is_phantom_data(&field.ty))
+        } else {
+            options.show
+        };
+        if is_shown {
+            shown.push(member);
+        }
+    }
+    Ok(shown)
+}
+
+/// Whether `ty` is `PhantomData<...>`, by any path.
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            path.path.segments.last().map_or(false, |segment| segment.ident == "PhantomData")
+        }
+        _ => false,
+    }
+}
+
+/// The name of the variable that the value of the field `member` is bound to in a match arm.
+fn binding(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("__field_{}", ident),
+        Member::Unnamed(index) => format_ident!("__field_{}", index.index),
+    }
+}
+
+/// Writes `name` and the `shown` fields with their `Debug` impl, eliding the remaining `fields`.
+/// Each shown field is paired with an expression that evaluates to a reference to its value.
+/// Elided positional fields are written as a single `..` after the shown ones, like
+/// `finish_non_exhaustive` does for named fields.
+fn write_shown(
+    name: &str,
+    fields: &Fields,
+    shown: impl Iterator<Item = (Member, TokenStream)>,
+) -> TokenStream {
+    let (members, values): (Vec<_>, Vec<_>) = shown.unzip();
+    let elided = members.len() != fields.len();
+    if let Fields::Unnamed(_) = fields {
+        let elision = elided.then(|| quote!(.field(&::core::format_args!(".."))));
+        let debug_tuple = quote!(__formatter.debug_tuple(#name)#(.field(#values))*#elision.finish());
+        // The name is empty when it is written before, with `name_with` or `inline_generics`.
+        // Without a name `debug_tuple` prints a single field with a trailing comma, like a
+        // 1-tuple, so the compact output is written by hand in that case.
+        return match &values[..] {
+            [value] if name.is_empty() && !elided => quote! {
+                if __formatter.alternate() {
+                    #debug_tuple
+                } else {
+                    __formatter.write_str("(")?;
+                    ::core::fmt::Debug::fmt(#value, __formatter)?;
+                    __formatter.write_str(")")
+                }
+            },
+            _ => debug_tuple,
+        };
+    }
+    let names = members.iter().map(|member| match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    });
+    let finish = if elided {
+        quote!(finish_non_exhaustive)
+    } else {
+        quote!(finish)
+    };
+    quote!(__formatter.debug_struct(#name)#(.field(#names, #values))*.#finish())
+}
+
+/// Writes `name` followed by the elided `fields`. If the formatter is in alternate mode (`{:#?}`),
+/// the elision goes on its own indented line, like the standard pretty printed `Debug` output.
+fn write_shallow(name: &str, fields: &Fields, options: &ContainerOptions) -> TokenStream {
+    let len = array_len(fields, options);
+    if options.builder {
+        return match fields {
+            _ if no_fields(fields, options) => quote!(__formatter.write_str(#name)),
+            Fields::Named(_) => quote!(__formatter.debug_struct(#name).finish_non_exhaustive()),
+            Fields::Unnamed(_) if name.is_empty() => {
+                // Without a name `debug_tuple` prints a single field with a trailing comma, like a
+                // 1-tuple, so the output is written the same way by hand. Only the length of an
+                // array has to be formatted.
+                match len {
+                    Some(len) => quote! {
+                        if __formatter.alternate() {
+                            ::core::write!(__formatter, "(\n    [..; {}],\n)", #len)
+                        } else {
+                            ::core::write!(__formatter, "([..; {}])", #len)
+                        }
+                    },
+                    None => quote! {
+                        if __formatter.alternate() {
+                            __formatter.write_str("(\n    ..,\n)")
+                        } else {
+                            __formatter.write_str("(..)")
+                        }
+                    },
+                }
+            }
+            Fields::Unnamed(_) => {
+                let elided = match len {
+                    Some(len) => quote!(::core::format_args!("[..; {}]", #len)),
+                    None => quote!(::core::format_args!("..")),
+                };
+                quote!(__formatter.debug_tuple(#name).field(&#elided).finish())
+            }
+            Fields::Unit => quote!(__formatter.write_str(#name)),
+        };
+    }
+    // The text is never used as a format string, since it may come from a `rename`, `prefix` or
+    // `elision` that must be printed as is.
+    let write = |(before_len, after_len): (String, String)| match len {
+        Some(len) => quote! {
+            ::core::write!(__formatter, "{}{}{}{}", #name, #before_len, #len, #after_len)
+        },
+        None => write_sized(&output_text(name, &before_len, options), &after_len, options),
+    };
+    let suffix = fields_suffix(fields, options, false);
+    let alternate_suffix = fields_suffix(fields, options, true);
+    // Only the name is printed with `json_tag`, so the output is the same in alternate mode.
+    if suffix == alternate_suffix || options.json_tag {
+        write(suffix)
+    } else {
+        let write_alternate = write(alternate_suffix);
+        let write = write(suffix);
+        quote! {
+            if __formatter.alternate() {
+                #write_alternate
+            } else {
+                #write
+            }
+        }
+    }
+}
+
+/// The whole output for `name` followed by the text of the elided fields: the `format` template
+/// with both filled in, the JSON object of `json_tag` with only the name, or just the two of them.
+fn output_text(name: &str, fields: &str, options: &ContainerOptions) -> String {
+    if options.json_tag {
+        return format!("{{\"type\":\"{}\"}}", json_escape(name));
+    }
+    // Without a type name the fields start the output, so they aren't separated from it, as in
+    // `{ id, .. }` rather than ` { id, .. }`.
+    let fields = if options.skip_type_name && name.is_empty() {
+        fields.trim_start()
+    } else {
+        fields
+    };
+    match &options.format {
+        Some(template) => template.apply(name, fields),
+        None => format!("{name}{fields}"),
+    }
+}
+
+/// `text` escaped to be the contents of a JSON string.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            // The other control characters are the only ones that JSON requires to be escaped.
+            c if (c as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// With `skip_type_name`, rejects the types for which nothing would be printed: unions and structs
+/// without fields.
+fn check_skip_type_name(input: &DeriveInput, options: &ContainerOptions) -> syn::Result<()> {
+    if !options.skip_type_name {
+        return Ok(());
+    }
+    match &input.data {
+        Data::Struct(data_struct) if data_struct.fields.is_empty() => Err(syn::Error::new_spanned(
+            &input.ident,
+            "`skip_type_name` can't be used on structs without fields",
+        )),
+        Data::Union(_) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "`skip_type_name` can't be used on unions",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The attribute that only describes the output with the fields elided, either a `format` template,
+/// `json_tag`, `size` or `pretty_indent`, if any.
+fn elided_only(options: &ContainerOptions) -> Option<&'static str> {
+    if options.format.is_some() {
+        Some("format")
+    } else if options.json_tag {
+        Some("json_tag")
+    } else if options.size {
+        Some("size")
+    } else if options.pretty_indent.is_some() {
+        Some("pretty_indent")
+    } else {
+        None
+    }
+}
+
+/// With the `size` option, the text before the size of the value and the text after it, which are
+/// printed where there are no fields to put it after, as in `MyEnum::B /*1B*/`.
+fn size_suffix(options: &ContainerOptions) -> (String, String) {
+    if options.size {
+        (" /*".to_owned(), "B*/".to_owned())
+    } else {
+        (String::new(), String::new())
+    }
+}
+
+/// Writes `text`, followed with the `size` option by the size of the value and `after`.
+fn write_sized(text: &str, after: &str, options: &ContainerOptions) -> TokenStream {
+    if options.size {
+        quote! {
+            ::core::write!(__formatter, "{}{}{}", #text, ::core::mem::size_of_val(self), #after)
+        }
+    } else {
+        quote!(__formatter.write_str(#text))
+    }
+}
+
+/// The error for shown fields of a type with the `attr` attribute returned by `elided_only`.
+fn shown_with(shown: &[Member], attr: &str) -> syn::Error {
+    let message = format!("`show` and `skip` can't be used together with `{attr}`");
+    syn::Error::new_spanned(&shown[0], message)
+}
+
+/// Writes `name` followed by the integer value of the unit variant `variant`, converted to the
+/// `repr` type, as in `Opcode::Ping(1)`.
+fn write_repr(
+    name: &str,
+    variant: &Ident,
+    repr: &Type,
+    options: &ContainerOptions,
+) -> TokenStream {
+    // A cast of the path rather than of `*self`, which would move out of it for non-`Copy` types.
+    let value = quote!(Self::#variant as #repr);
+    if options.builder {
+        quote!(__formatter.debug_tuple(#name).field(&(#value)).finish())
+    } else {
+        quote!(::core::write!(__formatter, "{}({})", #name, #value))
+    }
+}
+
+/// With `array_len`, the length of the array if `fields` is a single unnamed field of an array
+/// type, like `[u8; N]`. The length is passed as an argument to the format string, so that named
+/// constants and const parameters are printed with their value.
+fn array_len<'a>(fields: &'a Fields, options: &ContainerOptions) -> Option<&'a Expr> {
+    match fields {
+        Fields::Unnamed(unnamed) if options.array_len && unnamed.unnamed.len() == 1 => {
+            match &unnamed.unnamed[0].ty {
+                Type::Array(array) => Some(&array.len),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether `fields` is known to be empty, as for `A()` and `A {}`. The fields listed by
+/// `shallow_debug!` as `(..)` or `{ .. }` never are.
+fn no_fields(fields: &Fields, options: &ContainerOptions) -> bool {
+    fields.is_empty() && !options.opaque_fields
+}
+
+/// The text that comes after the name, eliding the `fields`. When the length of an array or the
+/// size of the value is printed, the text is split in the part before it and the part after it.
+/// Otherwise the second part is empty. Like with `#[derive(Debug)]`, there is no text after the
+/// name when there are no fields, even if they are written as `A()` or `A {}`, since nothing is
+/// elided. Only the count of `show_field_count` and the size are still printed, since they tell
+/// something about the value, and the `unit_suffix` of unit structs and variants.
+fn fields_suffix(fields: &Fields, options: &ContainerOptions, alternate: bool) -> (String, String) {
+    let (before_size, after_size) = size_suffix(options);
+    if let (Fields::Unit, Some(unit_suffix)) = (fields, &options.unit_suffix) {
+        return (format!("{unit_suffix}{before_size}"), after_size);
+    }
+    if no_fields(fields, options) && !options.show_field_count {
+        return (before_size, after_size);
+    }
+    let elision = options.elision.as_deref().unwrap_or("..");
+    let count = if options.show_field_count {
+        fields.len().to_string()
+    } else {
+        String::new()
+    };
+    let elided = format!("{elision}{count}{before_size}");
+    let indent = " ".repeat(options.pretty_indent.unwrap_or(4));
+    let (suffix, close) = match fields {
+        Fields::Named(named) if options.field_names => {
+            let names = named.named.iter()
+                .map(|field| field.ident.as_ref().unwrap().unraw().to_string());
+            if alternate {
+                let names = names.map(|name| format!("{indent}{name},\n")).collect::<String>();
+                (format!(" {{\n{names}{indent}{elided}"), "\n}")
+            } else {
+                let names = names.map(|name| format!("{name}, ")).collect::<String>();
+                (format!(" {{ {names}{elided}"), " }")
+            }
+        }
+        Fields::Unnamed(_) if array_len(fields, options).is_some() => {
+            return if alternate {
+                (format!("(\n{indent}[{elision}; "), "]\n)".to_owned())
+            } else {
+                (format!("([{elision}; "), "])".to_owned())
+            };
+        }
+        Fields::Named(_) if alternate => (format!(" {{\n{indent}{elided}"), "\n}"),
+        Fields::Named(_) => (format!("{{{elided}"), "}"),
+        Fields::Unnamed(_) if alternate => (format!("(\n{indent}{elided}"), "\n)"),
+        Fields::Unnamed(_) => (format!("({elided}"), ")"),
+        Fields::Unit => (before_size, ""),
+    };
+    let close = format!("{after_size}{close}");
+    if options.size {
+        (suffix, close)
+    } else {
+        (suffix + &close, String::new())
+    }
+}
+
+/// The predicates declared by `generics`, either inline or in a `where` clause.
+///
+/// The shallow impl never needs any of them, but they all have to be forwarded anyway: the type is
+/// only well formed when its bounds hold, and rustc requires the impl to prove that. What matters
+/// is that no *extra* bounds are added (not even `T: Debug`), so the impl is as permissive as the
+/// type itself.
+fn generic_bounds(generics: &Generics) -> Vec<TokenStream> {
+    let inline = generics.params.iter()
+        .flat_map(|param| match param {
+            GenericParam::Lifetime(lifetime) => {
+                let ident = &lifetime.lifetime;
+                lifetime.bounds.iter().map(|bound| quote!(#ident: #bound)).collect()
+            }
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                ty.bounds.iter().map(|bound| quote!(#ident: #bound)).collect()
+            }
+            GenericParam::Const(_) => Vec::new(),
+        });
+    let where_clause = generics.where_clause.iter()
+        .flat_map(|clause| clause.predicates.iter().flat_map(split_predicate));
+    dedup_predicates(inline.chain(where_clause))
+}
+
+/// The relaxed bounds, like `T: ?Sized`, declared by `generics` either inline or in a `where`
+/// clause.
+fn relaxed_bounds(generics: &Generics) -> Vec<TokenStream> {
+    let is_relaxed = |bound: &&TypeParamBound| matches!(
+        bound,
+        TypeParamBound::Trait(TraitBound { modifier: TraitBoundModifier::Maybe(_), .. }),
+    );
+    let inline = generics.type_params()
+        .flat_map(|param| {
+            let ident = &param.ident;
+            param.bounds.iter().filter(is_relaxed).map(move |bound| quote!(#ident: #bound))
+        });
+    let where_clause = generics.where_clause.iter()
+        .flat_map(|clause| clause.predicates.iter())
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => Some(predicate),
+            _ => None,
+        })
+        .flat_map(|predicate| {
+            let ty = &predicate.bounded_ty;
+            predicate.bounds.iter().filter(is_relaxed).map(move |bound| quote!(#ty: #bound))
+        });
+    inline.chain(where_clause).collect()
+}
+
+/// Splits a predicate like `T: Clone + Send` into one predicate per bound, so that they can be
+/// deduplicated individually.
+fn split_predicate(predicate: &WherePredicate) -> Vec<TokenStream> {
+    match predicate {
+        WherePredicate::Lifetime(predicate) => {
+            let lifetime = &predicate.lifetime;
+            predicate.bounds.iter().map(|bound| quote!(#lifetime: #bound)).collect()
+        }
+        WherePredicate::Type(predicate) => {
+            let lifetimes = &predicate.lifetimes;
+            let ty = &predicate.bounded_ty;
+            predicate.bounds.iter().map(|bound| quote!(#lifetimes #ty: #bound)).collect()
+        }
+        predicate => vec![predicate.to_token_stream()],
+    }
+}
+
+/// Removes repeated predicates, keeping the first occurrence of each.
+fn dedup_predicates(predicates: impl Iterator<Item = TokenStream>) -> Vec<TokenStream> {
+    let mut seen = HashSet::new();
+    predicates
+        .filter(|predicate| seen.insert(predicate.to_string()))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn fmt_body(input: &DeriveInput) -> (String, Vec<String>) {
+        // Not `from_attrs`, so that the output doesn't depend on the environment.
+        let options = ContainerOptions::from_attrs_with_style(&input.attrs, Default::default())
+            .unwrap();
+        let (body, bounds) = build_fmt_body(input, FmtTrait::Debug, &options).unwrap();
+        (body.to_string(), bounds.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn enum_body() {
+        let input = parse_quote! {
+            enum MyEnum {
+                A(u8),
+                B { x: u8 },
+                C,
+            }
+        };
+        let expected = quote! {
+            match self {
+                MyEnum::A(..) => if __formatter.alternate() {
+                    __formatter.write_str("MyEnum::A(\n    ..\n)")
+                } else {
+                    __formatter.write_str("MyEnum::A(..)")
+                },
+                MyEnum::B{ .. } => if __formatter.alternate() {
+                    __formatter.write_str("MyEnum::B {\n    ..\n}")
+                } else {
+                    __formatter.write_str("MyEnum::B{..}")
+                },
+                MyEnum::C => __formatter.write_str("MyEnum::C"),
+            }
+        };
+        assert_eq!(fmt_body(&input), (expected.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn fmt_impl() {
+        let input: DeriveInput = parse_quote! {
+            #[shallow_debug(inline(never))]
+            struct MyStruct;
+        };
+        let options = ContainerOptions::from_attrs_with_style(&input.attrs, Default::default())
+            .unwrap();
+        let body = quote!(__formatter.write_str("MyStruct"));
+        let expected = quote! {
+            #[automatically_derived]
+            #[allow(unused_qualifications, deprecated)]
+            impl ::core::fmt::Debug for MyStruct {
+                #[inline(never)]
+                fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    __formatter.write_str("MyStruct")
+                }
+            }
+        };
+        let fmt_impl = impl_fmt(&input, FmtTrait::Debug, &options, &[], &body);
+        assert_eq!(fmt_impl.to_string(), expected.to_string());
+
+        let options = ContainerOptions::default();
+        let fmt_impl = impl_fmt(&input, FmtTrait::Debug, &options, &[], &body);
+        assert!(fmt_impl.to_string().contains("# [inline] fn fmt"));
+    }
+
+    #[test]
+    fn struct_body() {
+        let input = parse_quote! {
+            struct MyStruct {
+                #[shallow_debug(show)]
+                id: u32,
+                name: String,
+            }
+        };
+        let expected = quote! {
+            __formatter.debug_struct("MyStruct").field("id", &self.id).finish_non_exhaustive()
+        };
+        assert_eq!(fmt_body(&input), (expected.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn shown_positional_body() {
+        let input = parse_quote! {
+            enum Event {
+                Clicked(u8, #[shallow_debug(show)] u8),
+            }
+        };
+        let expected = quote! {
+            match self {
+                Event::Clicked{ 1: __field_1, .. } => __formatter.debug_tuple("Event::Clicked")
+                    .field(__field_1)
+                    .field(&::core::format_args!(".."))
+                    .finish(),
+            }
+        };
+        assert_eq!(fmt_body(&input), (expected.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn transparent_body() {
+        let input = parse_quote! {
+            #[shallow_debug(transparent)]
+            struct Wrapper<T>(Vec<T>);
+        };
+        let expected = quote!(::core::fmt::Debug::fmt(&self.0, __formatter));
+        let bound = quote!(Vec<T>: ::core::fmt::Debug);
+        assert_eq!(fmt_body(&input), (expected.to_string(), vec![bound.to_string()]));
+    }
+
+    #[test]
+    fn self_forwarding_types() {
+        let ident = format_ident!("Node");
+        let forwards = |ty: Type| forwards_to_self(&ty, &ident);
+        assert!(forwards(parse_quote!(Node)));
+        assert!(forwards(parse_quote!(Box<Self>)));
+        assert!(forwards(parse_quote!(&'a std::sync::Arc<Node<T>>)));
+        assert!(!forwards(parse_quote!(Option<Box<Node>>)));
+        assert!(!forwards(parse_quote!(Vec<Node>)));
+        assert!(!forwards(parse_quote!(other::Box<u8>)));
+    }
+
+    #[test]
+    fn impl_header() {
+        let input: DeriveInput = parse_quote!(struct Plain;);
+        let header = build_impl_header(&input, Some(FmtTrait::Debug), &[]);
+        assert_eq!(header.to_string(), quote!(impl ::core::fmt::Debug for Plain).to_string());
+
+        let input: DeriveInput = parse_quote! {
+            struct Generic<'a, T: Clone = u8, const N: usize = 4>(&'a [T; N]);
+        };
+        let bounds = generic_bounds(&input.generics);
+        let header = build_impl_header(&input, Some(FmtTrait::Display), &bounds);
+        let expected = quote! {
+            impl<'a, T, const N: usize> ::core::fmt::Display for Generic<'a, T, N>
+            where
+                T: Clone
+        };
+        assert_eq!(header.to_string(), expected.to_string());
+
+        let header = build_impl_header(&input, None::<TokenStream>, &[]);
+        let expected = quote!(impl<'a, T, const N: usize> Generic<'a, T, N>);
+        assert_eq!(header.to_string(), expected.to_string());
+
+        let input: DeriveInput = parse_quote! {
+            struct Matrix<const R: usize, T, const C: usize>([[T; C]; R]);
+        };
+        let header = build_impl_header(&input, Some(FmtTrait::Debug), &[]);
+        let expected = quote! {
+            impl<const R: usize, T, const C: usize> ::core::fmt::Debug for Matrix<R, T, C>
+        };
+        assert_eq!(header.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn literal_outputs() {
+        // Outputs that are known when the derive runs are written as they are, without going
+        // through the formatting machinery.
+        let inputs: [DeriveInput; 5] = [
+            parse_quote! {
+                #[shallow_debug(rename = "{Renamed}", discriminant, elision = "...")]
+                enum MyEnum {
+                    A(u8),
+                    B { x: u8 },
+                    C = 1,
+                    #[shallow_debug(skip)]
+                    D,
+                }
+            },
+            parse_quote!(#[shallow_debug(field_names, variant_only)] enum E { A { x: u8 } }),
+            parse_quote!(#[shallow_debug(format = "<{name}>{fields}")] struct S(u8);),
+            parse_quote!(#[shallow_debug(builder)] struct Unit;),
+            parse_quote!(#[shallow_debug(json_tag)] enum J { A { x: u8 }, B(u8) }),
+        ];
+        for input in &inputs {
+            let (body, _) = fmt_body(input);
+            assert!(body.contains("write_str") && !body.contains("write !"), "{body}");
+        }
+        // Not even the alternate mode changes the output of `json_tag`.
+        assert!(!fmt_body(&inputs[4]).0.contains("alternate"));
+
+        let input = parse_quote!(#[shallow_debug(builder, inline_generics)] struct W<T>(T););
+        let (body, _) = fmt_body(&input);
+        // Only the type arguments are formatted.
+        assert_eq!(body.matches("write !").count(), 1, "{body}");
+        assert!(body.contains(&quote!(__formatter.write_str("(..)")).to_string()), "{body}");
+    }
+
+    #[test]
+    fn json_escape() {
+        assert_eq!(super::json_escape("MyEnum::A"), "MyEnum::A");
+        assert_eq!(super::json_escape(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(super::json_escape(r"a\b"), r"a\\b");
+        assert_eq!(super::json_escape("a\nb\t\u{1}\u{7f}é"), "a\\nb\\t\\u0001\u{7f}é");
+    }
+
+    #[test]
+    fn bound_extra() {
+        let input: DeriveInput = parse_quote! {
+            #[shallow_debug(transparent, bound_extra = "T: 'static, T: Clone + Send")]
+            struct Wrapper<T: Clone>(T) where T: Copy;
+        };
+        let options = ContainerOptions::from_attrs_with_style(&input.attrs, Default::default())
+            .unwrap();
+        let (_, required) = build_fmt_body(&input, FmtTrait::Debug, &options).unwrap();
+        let bounds = impl_bounds(&input, &options, required);
+        let expected = [
+            quote!(T: Clone),
+            quote!(T: Copy),
+            quote!(T: ::core::fmt::Debug),
+            quote!(T: 'static),
+            quote!(T: Send),
+        ];
+        let strings = |bounds: &[TokenStream]| {
+            bounds.iter().map(ToString::to_string).collect::<Vec<_>>()
+        };
+        assert_eq!(strings(&bounds), strings(&expected));
+    }
+
+    #[test]
+    fn renamed_name_with() {
+        let inputs: [DeriveInput; 2] = [
+            parse_quote!(#[shallow_debug(rename = "Renamed", name_with = "name")] struct S(u8);),
+            parse_quote!(#[shallow_debug(rename_ident = Renamed, name_with = "name")] union U {
+                a: u8,
+            }),
+        ];
+        for input in inputs {
+            let options = ContainerOptions::from_attrs_with_style(&input.attrs, Default::default())
+                .unwrap();
+            let err = build_fmt_body(&input, FmtTrait::Debug, &options).err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                "`name_with` replaces the name of structs and unions, so it can't be used \
+                 together with `rename` or `rename_ident`",
+            );
+        }
+    }
+
+    #[test]
+    fn scoped_once() {
+        let input: foreign::ForeignType = parse_quote!(external::Message { Ping, Data(..) });
+        let output = expand_foreign(&input.to_derive_input(), input.use_item()).unwrap();
+        let output = output.to_string();
+        assert!(output.starts_with("const _ : () = { use external :: Message as Message ;"));
+        assert_eq!(output.matches("const _").count(), 1);
+    }
+}
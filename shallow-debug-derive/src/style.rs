@@ -0,0 +1,77 @@
+//! The default output style, which can be set for a whole build with the `SHALLOW_DEBUG_STYLE`
+//! environment variable.
+
+use std::env::{self, VarError};
+
+use proc_macro2::Span;
+use syn::Data;
+
+/// The environment variable read by [`Style::from_env`].
+pub(crate) const ENV_VAR: &str = "SHALLOW_DEBUG_STYLE";
+
+const STYLES: &[&str] = &["default", "builder", "variant_only"];
+
+/// Container options that are enabled for every type, unless its attributes say otherwise.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub(crate) struct Style {
+    /// As if every type had `#[shallow_debug(builder)]`.
+    pub builder: bool,
+    /// As if every enum had `#[shallow_debug(variant_only)]`.
+    pub variant_only: bool,
+}
+
+impl Style {
+    /// The style set in the environment, or the default one if the variable isn't set.
+    pub fn from_env() -> syn::Result<Self> {
+        let value = match env::var(ENV_VAR) {
+            Ok(value) => value,
+            Err(VarError::NotPresent) => return Ok(Style::default()),
+            Err(VarError::NotUnicode(_)) => {
+                let message = format!("`{ENV_VAR}` is not valid unicode");
+                return Err(syn::Error::new(Span::call_site(), message));
+            }
+        };
+        Style::parse(&value).map_err(|message| syn::Error::new(Span::call_site(), message))
+    }
+
+    /// The style for a type with the given `data`. Only enums have variants to print alone.
+    pub fn for_data(self, data: &Data) -> Self {
+        Style { variant_only: self.variant_only && matches!(data, Data::Enum(_)), ..self }
+    }
+
+    /// Parses a comma separated list of styles, like `builder,variant_only`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut style = Style::default();
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match name {
+                "default" => {}
+                "builder" => style.builder = true,
+                "variant_only" => style.variant_only = true,
+                _ => {
+                    return Err(format!(
+                        "unknown `{ENV_VAR}` style `{name}`, expected one of: {}",
+                        STYLES.join(", "),
+                    ));
+                }
+            }
+        }
+        Ok(style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Style::parse(""), Ok(Style::default()));
+        assert_eq!(Style::parse("default"), Ok(Style::default()));
+        assert_eq!(Style::parse("builder"), Ok(Style { builder: true, variant_only: false }));
+        assert_eq!(
+            Style::parse(" variant_only , builder,"),
+            Ok(Style { builder: true, variant_only: true }),
+        );
+        assert!(Style::parse("builder,compact").unwrap_err().contains("`compact`"));
+    }
+}
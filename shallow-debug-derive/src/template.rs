@@ -0,0 +1,107 @@
+//! Templates for the whole shallow output, for the `format` attribute.
+
+/// The placeholders that can be used in a template.
+const PLACEHOLDERS: &[&str] = &["name", "fields"];
+
+/// A template like `[{name}]{fields}`, parsed when the derive runs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Part {
+    Text(String),
+    /// `{name}`, the name of the type or of the variant, like `MyEnum::A`.
+    Name,
+    /// `{fields}`, the elided fields, like `(..)` or `{ id, .. }`.
+    Fields,
+}
+
+impl Template {
+    /// Parses a template, in which `{{` and `}}` are literal braces, like in format strings.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or_else(|| {
+                        "unmatched `{` in `format`, use `{{` to print a brace".to_owned()
+                    })?;
+                    let part = match &rest[..end] {
+                        "name" => Part::Name,
+                        "fields" => Part::Fields,
+                        placeholder => {
+                            let expected = PLACEHOLDERS.iter()
+                                .map(|placeholder| format!("`{{{placeholder}}}`"))
+                                .collect::<Vec<_>>();
+                            return Err(format!(
+                                "unknown placeholder `{{{placeholder}}}` in `format`, expected one \
+                                 of: {}",
+                                expected.join(", "),
+                            ));
+                        }
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(part);
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => {
+                    return Err("unmatched `}` in `format`, use `}}` to print a brace".to_owned());
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+
+    /// The output with the placeholders replaced by `name` and `fields`.
+    pub fn apply(&self, name: &str, fields: &str) -> String {
+        self.parts.iter()
+            .map(|part| match part {
+                Part::Text(text) => text.as_str(),
+                Part::Name => name,
+                Part::Fields => fields,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let template = Template::parse("[{name}]{fields}").unwrap();
+        assert_eq!(template.apply("MyEnum::A", "(..)"), "[MyEnum::A](..)");
+        let template = Template::parse("{{{name}}} {fields} {name}").unwrap();
+        assert_eq!(template.apply("A", "{..}"), "{A} {..} A");
+        assert_eq!(Template::parse("plain").unwrap().apply("A", "(..)"), "plain");
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Template::parse("{names}").unwrap_err().contains("`{names}`"));
+        assert!(Template::parse("{").unwrap_err().contains("unmatched `{`"));
+        assert!(Template::parse("{name").unwrap_err().contains("unmatched `{`"));
+        assert!(Template::parse("name}").unwrap_err().contains("unmatched `}`"));
+        assert!(Template::parse("{}").unwrap_err().contains("`{}`"));
+    }
+}
@@ -8,6 +8,8 @@
 //! the inner values are printed, they don't have to implement `Debug`, so this can also be useful
 //! in highly generic code where you just want a quick and simple way to get debug information.
 //!
+//! The generated code only refers to `core`, so the derive also works in `#![no_std]` crates.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -22,124 +24,37 @@
 //! let value: MyEnum<i32, &str, usize> = MyEnum::A(123);
 //! assert_eq!(format!("{value:?}"), "MyEnum::A(..)");
 //! ```
-
-use syn::{Data, Fields, GenericParam};
-use quote::{quote, ToTokens};
-
-/// A derive macro that is able to implement `Debug` for any type, without requiring it's inner
-/// types to also implement the `Debug` trait. In order to do this, the `Debug` impl that is
-/// generated is "shallow", meaning it will only print the enum variant names, but not their
-/// internal values. You can also `#[derive(ShallowDebug)]` for structs and unions, but it will not
-/// print the field values. In general this is more useful for enums, since the variant can
-/// already tell you useful information.
-#[proc_macro_derive(ShallowDebug)]
-pub fn derive_shallow_debug(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = syn::parse_macro_input!(stream as syn::DeriveInput);
-
-    let ident = &input.ident;
-    let fmt_body = match &input.data {
-        Data::Enum(data_enum) => {
-            let variants = data_enum.variants.iter()
-                .map(|variant| {
-                    let variant_ident = &variant.ident;
-                    match &variant.fields {
-                        Fields::Named(_) => {
-                            let fmt = format!("{ident}::{variant_ident}{{{{..}}}}");
-                            quote!(#ident::#variant_ident{..} => write!(f, #fmt))
-                        }
-                        Fields::Unnamed(_) => {
-                            let fmt = format!("{ident}::{variant_ident}(..)");
-                            quote!(#ident::#variant_ident(..) => write!(f, #fmt))
-                        }
-                        Fields::Unit => {
-                            let fmt = format!("{ident}::{variant_ident}");
-                            quote!(#ident::#variant_ident => write!(f, #fmt))
-                        }
-                    }
-                });
-
-            quote! {
-                match self {
-                    #(#variants,)*
-                }
-            }
-        }
-        Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(_) => {
-                let fmt = format!("{ident}{{{{..}}}}");
-                quote!(write!(f, #fmt))
-            }
-            Fields::Unnamed(_) => {
-                let fmt = format!("{ident}(..)");
-                quote!(write!(f, #fmt))
-            }
-            Fields::Unit => {
-                let fmt = format!("{ident}");
-                quote!(write!(f, #fmt))
-            }
-        }
-
-        Data::Union(_) => {
-            let fmt = format!("{ident}");
-            quote!(write!(f, #fmt))
-        }
-    };
-
-    let bounds = input.generics.params.iter()
-        .filter_map(|param| match param {
-            GenericParam::Lifetime(lifetime) if lifetime.bounds.is_empty() => None,
-            GenericParam::Lifetime(lifetime) => {
-                let bounds = &lifetime.bounds;
-                let ident = &lifetime.lifetime;
-                Some(quote!(#ident: #bounds))
-            }
-            GenericParam::Type(ty) if ty.bounds.is_empty() => None,
-            GenericParam::Type(ty) => {
-                let bounds = &ty.bounds;
-                let ident = &ty.ident;
-                Some(quote!(#ident: #bounds))
-            }
-            GenericParam::Const(_) => None,
-        })
-        .chain({
-            input.generics.where_clause.iter()
-                .flat_map(|clause| clause.predicates.iter().map(ToTokens::to_token_stream))
-        });
-
-    let ty_vars = input.generics.params.iter()
-        .map(|param| match param {
-            GenericParam::Lifetime(lifetime) => lifetime.lifetime.to_token_stream(),
-            GenericParam::Type(ty) => {
-                let ident = &ty.ident;
-                if let Some(default) = &ty.default {
-                    quote!(#ident = #default)
-                } else {
-                    ident.to_token_stream()
-                }
-            }
-            GenericParam::Const(cons) => cons.to_token_stream(),
-        })
-        .collect::<Vec<_>>();
-
-    // The `impl Debug for <type> where ...` part
-    let impl_debug = if ty_vars.is_empty() {
-        quote! {
-            impl std::fmt::Debug for #ident
-        }
-    } else {
-        quote! {
-            impl<#(#ty_vars),*> std::fmt::Debug for #ident<#(#ty_vars),*>
-            where
-                #(#bounds),*
-        }
-    };
-
-    quote! {
-        #impl_debug {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                #fmt_body
-            }
-        }
-    }.into()
+//!
+//! For values of types that don't implement `Debug` at all, the [`Shallow`] wrapper prints just
+//! the name of the type.
+
+#![no_std]
+// Everything exported by this crate is meant to end up in `Debug` output.
+#![deny(missing_debug_implementations)]
+
+use core::any::type_name;
+use core::fmt;
+
+pub use shallow_debug_derive::{shallow_debug, shallow_debug_alias, ShallowDebug, ShallowDisplay};
+#[cfg(feature = "defmt")]
+pub use shallow_debug_derive::ShallowDefmt;
+
+/// A wrapper whose `Debug` impl only prints the name of the wrapped type, for ad-hoc shallow
+/// formatting of values whose type can't or doesn't derive [`ShallowDebug`].
+///
+/// ```rust
+/// # use shallow_debug::Shallow;
+/// let values = vec![1u32, 2, 3];
+///
+/// assert_eq!(format!("{:?}", Shallow(&values[..])), "Shallow(&[u32])");
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shallow<T>(pub T);
+
+impl<T> fmt::Debug for Shallow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Shallow")
+            .field(&format_args!("{}", type_name::<T>()))
+            .finish()
+    }
 }
-
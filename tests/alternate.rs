@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+enum MyEnum {
+    A(u8),
+    B { value: u8 },
+    C,
+}
+
+#[test]
+fn compact() {
+    assert_eq!(format!("{:?}", MyEnum::A(1)), "MyEnum::A(..)");
+    assert_eq!(format!("{:?}", MyEnum::B { value: 1 }), "MyEnum::B{..}");
+    assert_eq!(format!("{:?}", MyEnum::C), "MyEnum::C");
+}
+
+#[test]
+fn alternate() {
+    assert_eq!(format!("{:#?}", MyEnum::A(1)), "MyEnum::A(\n    ..\n)");
+    assert_eq!(format!("{:#?}", MyEnum::B { value: 1 }), "MyEnum::B {\n    ..\n}");
+    assert_eq!(format!("{:#?}", MyEnum::C), "MyEnum::C");
+}
+
+#[test]
+fn alternate_struct() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_field_count)]
+    struct Pair(u8, u8);
+
+    assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(..2)");
+    assert_eq!(format!("{:#?}", Pair(1, 2)), "Pair(\n    ..2\n)");
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(builder)]
+enum Built {
+    A(u8),
+    B { value: u8 },
+    C,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(builder)]
+struct BuiltStruct {
+    value: u8,
+}
+
+#[derive(Debug)]
+struct Outer {
+    tuple: Built,
+    named: Built,
+    unit: Built,
+    structure: BuiltStruct,
+}
+
+#[test]
+fn builder() {
+    assert_eq!(format!("{:?}", Built::A(1)), "Built::A(..)");
+    assert_eq!(format!("{:?}", Built::B { value: 1 }), "Built::B { .. }");
+    assert_eq!(format!("{:?}", Built::C), "Built::C");
+    assert_eq!(format!("{:?}", BuiltStruct { value: 1 }), "BuiltStruct { .. }");
+}
+
+#[test]
+fn builder_nested() {
+    let outer = Outer {
+        tuple: Built::A(1),
+        named: Built::B { value: 1 },
+        unit: Built::C,
+        structure: BuiltStruct { value: 1 },
+    };
+    assert_eq!(
+        format!("{outer:?}"),
+        "Outer { tuple: Built::A(..), named: Built::B { .. }, unit: Built::C, structure: BuiltStruct { .. } }",
+    );
+    assert_eq!(
+        format!("{outer:#?}"),
+        "\
+Outer {
+    tuple: Built::A(
+        ..,
+    ),
+    named: Built::B { .. },
+    unit: Built::C,
+    structure: BuiltStruct { .. },
+}",
+    );
+}
+
+#[test]
+fn pretty_indent() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(pretty_indent = 0)]
+    enum Flat {
+        A(u8),
+        B { value: u8 },
+        C,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(pretty_indent = 4, field_names)]
+    struct Named {
+        id: u8,
+        name: u8,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(pretty_indent = 2, array_len)]
+    struct Buffer([u8; 3]);
+
+    assert_eq!(format!("{:#?}", Flat::A(1)), "Flat::A(\n..\n)");
+    assert_eq!(format!("{:#?}", Flat::B { value: 1 }), "Flat::B {\n..\n}");
+    assert_eq!(format!("{:#?}", Flat::C), "Flat::C");
+    // The compact output has no indentation to change.
+    assert_eq!(format!("{:?}", Flat::A(1)), "Flat::A(..)");
+    let named = Named { id: 1, name: 2 };
+    assert_eq!(format!("{named:#?}"), "Named {\n    id,\n    name,\n    ..\n}");
+    assert_eq!(format!("{:#?}", Buffer([0; 3])), "Buffer(\n  [..; 3]\n)");
+}
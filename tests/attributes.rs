@@ -0,0 +1,1251 @@
+#![allow(dead_code)]
+
+use shallow_debug::{ShallowDebug, ShallowDisplay};
+
+#[test]
+fn skip_variant() {
+    #[derive(ShallowDebug)]
+    enum Event {
+        Shown,
+        #[shallow_debug(skip)]
+        Named { id: u32 },
+        #[shallow_debug(skip)]
+        Unnamed(u32),
+        #[shallow_debug(skip)]
+        Unit,
+    }
+
+    assert_eq!(format!("{:?}", Event::Shown), "Event::Shown");
+    assert_eq!(format!("{:?}", Event::Named { id: 1 }), "Event::..");
+    assert_eq!(format!("{:?}", Event::Unnamed(1)), "Event::..");
+    assert_eq!(format!("{:?}", Event::Unit), "Event::..");
+}
+
+#[test]
+fn rename_struct() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "Renamed")]
+    struct Original {
+        id: u32,
+    }
+
+    assert_eq!(format!("{:?}", Original { id: 1 }), "Renamed{..}");
+}
+
+#[test]
+fn rename_path() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "net::tcp::Conn", name_fn, variants_const)]
+    enum Connection {
+        #[shallow_debug(rename = "state::Open")]
+        Open(u8),
+        Closed,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "::net::Socket", name_fn)]
+    struct Socket {
+        fd: i32,
+    }
+
+    assert_eq!(format!("{:?}", Connection::Open(1)), "net::tcp::Conn::state::Open(..)");
+    assert_eq!(format!("{:#?}", Connection::Open(1)), "net::tcp::Conn::state::Open(\n    ..\n)");
+    assert_eq!(format!("{:?}", Connection::Closed), "net::tcp::Conn::Closed");
+    assert_eq!(Connection::Open(1).shallow_name(), "state::Open");
+    assert_eq!(Connection::VARIANTS, ["state::Open", "Closed"]);
+    assert_eq!(format!("{:?}", Socket { fd: 0 }), "::net::Socket{..}");
+    assert_eq!(Socket { fd: 0 }.shallow_name(), "::net::Socket");
+}
+
+#[test]
+fn rename_variants() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "Req")]
+    enum Request {
+        #[shallow_debug(rename = "HTTP Request")]
+        HttpReq(String),
+        #[shallow_debug(rename = "none")]
+        Empty,
+        Other,
+    }
+
+    assert_eq!(format!("{:?}", Request::HttpReq("/".into())), "Req::HTTP Request(..)");
+    assert_eq!(format!("{:?}", Request::Empty), "Req::none");
+    assert_eq!(format!("{:?}", Request::Other), "Req::Other");
+}
+
+#[test]
+fn rename_ident() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename_ident = Req, name_fn)]
+    enum Request {
+        #[shallow_debug(rename_ident = Get)]
+        HttpGet(String),
+        #[shallow_debug(rename_ident = r#type)]
+        Kind,
+        #[shallow_debug(rename = "as string")]
+        Other,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename_ident = Conn)]
+    struct Connection(u8);
+
+    assert_eq!(format!("{:?}", Request::HttpGet("/".into())), "Req::Get(..)");
+    assert_eq!(format!("{:?}", Request::Kind), "Req::type");
+    assert_eq!(Request::Kind.shallow_name(), "type");
+    assert_eq!(format!("{:?}", Request::Other), "Req::as string");
+    assert_eq!(format!("{:?}", Connection(1)), "Conn(..)");
+}
+
+#[test]
+fn show_field_count() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_field_count)]
+    enum Shapes {
+        Zero(),
+        One(u8),
+        Three(u8, u8, u8),
+        NamedZero {},
+        NamedTwo { a: u8, b: u8 },
+        Unit,
+    }
+
+    assert_eq!(format!("{:?}", Shapes::Zero()), "Shapes::Zero(..0)");
+    assert_eq!(format!("{:?}", Shapes::One(1)), "Shapes::One(..1)");
+    assert_eq!(format!("{:?}", Shapes::Three(1, 2, 3)), "Shapes::Three(..3)");
+    assert_eq!(format!("{:?}", Shapes::NamedZero {}), "Shapes::NamedZero{..0}");
+    assert_eq!(format!("{:?}", Shapes::NamedTwo { a: 1, b: 2 }), "Shapes::NamedTwo{..2}");
+    assert_eq!(format!("{:?}", Shapes::Unit), "Shapes::Unit");
+}
+
+#[test]
+fn show_field_count_struct() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_field_count)]
+    struct Pair(u8, u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_field_count)]
+    struct Single {
+        value: u8,
+    }
+
+    assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(..2)");
+    assert_eq!(format!("{:?}", Single { value: 1 }), "Single{..1}");
+}
+
+#[test]
+fn discriminant() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(discriminant)]
+    enum Code {
+        Ok = 200,
+        NotFound = 404,
+        Implicit,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(discriminant)]
+    #[repr(i8)]
+    enum Signed {
+        Neg = -1,
+        Hex = -0x10,
+        Shifted = 1 << 2,
+    }
+
+    assert_eq!(format!("{:?}", Signed::Neg), "Signed::Neg = -1");
+    assert_eq!(format!("{:?}", Signed::Hex), "Signed::Hex = -0x10");
+    assert_eq!(format!("{:?}", Signed::Shifted), "Signed::Shifted = 1 << 2");
+    assert_eq!(format!("{:?}", Code::Ok), "Code::Ok = 200");
+    assert_eq!(format!("{:?}", Code::NotFound), "Code::NotFound = 404");
+    assert_eq!(format!("{:?}", Code::Implicit), "Code::Implicit");
+}
+
+#[test]
+fn transparent() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent)]
+    struct Meters(f64);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent)]
+    struct Named<T> {
+        inner: T,
+    }
+
+    assert_eq!(format!("{:?}", Meters(1.5)), "1.5");
+    assert_eq!(format!("{:?}", Named { inner: "text" }), r#""text""#);
+    assert_eq!(format!("{:#?}", Named { inner: (1,) }), "(\n    1,\n)");
+}
+
+#[test]
+fn recursive() {
+    // The recursion ends with the value, unlike with a field of type `Box<List>`.
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent)]
+    struct List(Option<Box<List>>);
+
+    #[derive(ShallowDebug)]
+    enum Tree {
+        Node(Box<Tree>, Box<Tree>),
+        Leaf,
+    }
+
+    let list = List(Some(Box::new(List(None))));
+    assert_eq!(format!("{list:?}"), "Some(None)");
+    let tree = Tree::Node(Box::new(Tree::Leaf), Box::new(Tree::Leaf));
+    assert_eq!(format!("{tree:?}"), "Tree::Node(..)");
+}
+
+#[test]
+fn prefix() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(prefix = "net")]
+    enum State {
+        Idle(u8),
+        Closed,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(prefix = "net::tcp", rename = "Conn")]
+    struct Connection {
+        fd: i32,
+    }
+
+    assert_eq!(format!("{:?}", State::Idle(0)), "net::State::Idle(..)");
+    assert_eq!(format!("{:?}", State::Closed), "net::State::Closed");
+    assert_eq!(format!("{:?}", Connection { fd: 0 }), "net::tcp::Conn{..}");
+}
+
+#[test]
+fn field_names() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(field_names)]
+    struct User {
+        id: u32,
+        name: String,
+        email: String,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(field_names)]
+    enum Event {
+        Click { x: i32, y: i32 },
+        Key(char),
+        Empty {},
+    }
+
+    let user = User { id: 1, name: "a".into(), email: "b".into() };
+    assert_eq!(format!("{user:?}"), "User { id, name, email, .. }");
+    assert_eq!(format!("{user:#?}"), "User {\n    id,\n    name,\n    email,\n    ..\n}");
+    assert_eq!(format!("{:?}", Event::Click { x: 1, y: 2 }), "Event::Click { x, y, .. }");
+    assert_eq!(format!("{:?}", Event::Key('a')), "Event::Key(..)");
+    assert_eq!(format!("{:?}", Event::Empty {}), "Event::Empty");
+}
+
+#[test]
+fn raw_identifiers() {
+    #[derive(ShallowDebug)]
+    #[allow(non_camel_case_types)]
+    enum r#Keywords {
+        r#Match(u8),
+        r#Async,
+        r#Struct {
+            #[shallow_debug(show)]
+            r#type: u8,
+        },
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(field_names)]
+    struct Item {
+        r#type: u8,
+        r#fn: u8,
+    }
+
+    assert_eq!(format!("{:?}", Keywords::Match(1)), "Keywords::Match(..)");
+    assert_eq!(format!("{:?}", Keywords::r#Async), "Keywords::Async");
+    assert_eq!(format!("{:?}", Keywords::Struct { r#type: 1 }), "Keywords::Struct { type: 1 }");
+    assert_eq!(format!("{:?}", Item { r#type: 1, r#fn: 2 }), "Item { type, fn, .. }");
+}
+
+#[test]
+fn elision() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(elision = "...")]
+    enum Dots {
+        Tuple(u8),
+        Named { a: u8 },
+        Unit,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(elision = "<hidden>", show_field_count)]
+    struct Hidden(u8, u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(elision = "etc", field_names)]
+    struct Names {
+        a: u8,
+    }
+
+    assert_eq!(format!("{:?}", Dots::Tuple(1)), "Dots::Tuple(...)");
+    assert_eq!(format!("{:?}", Dots::Named { a: 1 }), "Dots::Named{...}");
+    assert_eq!(format!("{:?}", Dots::Unit), "Dots::Unit");
+    assert_eq!(format!("{:#?}", Dots::Tuple(1)), "Dots::Tuple(\n    ...\n)");
+    assert_eq!(format!("{:?}", Hidden(1, 2)), "Hidden(<hidden>2)");
+    assert_eq!(format!("{:?}", Names { a: 1 }), "Names { a, etc }");
+}
+
+#[test]
+fn name_fn() {
+    #[derive(ShallowDebug, ShallowDisplay)]
+    #[shallow_debug(name_fn, prefix = "net")]
+    enum State<T> {
+        Idle,
+        Connected(T),
+        #[shallow_debug(rename = "Closing...")]
+        Closing { code: u16 },
+        #[shallow_debug(skip)]
+        Internal,
+        r#Async,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_fn, rename = "Conn")]
+    struct Connection(u8);
+
+    const IDLE: &str = State::<()>::Idle.shallow_name();
+
+    assert_eq!(IDLE, "Idle");
+    assert_eq!(State::Connected(1).shallow_name(), "Connected");
+    assert_eq!(State::<()>::Closing { code: 1 }.shallow_name(), "Closing...");
+    assert_eq!(State::<()>::Internal.shallow_name(), "..");
+    assert_eq!(State::<()>::r#Async.shallow_name(), "Async");
+    assert_eq!(Connection(1).shallow_name(), "Conn");
+}
+
+#[test]
+fn array_len() {
+    const SIZE: usize = 8;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(array_len)]
+    struct Foo([u8; 4]);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(array_len)]
+    struct Computed([u8; SIZE * 2]);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(array_len)]
+    struct Generic<T, const N: usize>([T; N]);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(array_len, builder)]
+    enum Packet {
+        Header([u8; 2]),
+        Payload(Vec<u8>),
+        Pair([u8; 2], u8),
+    }
+
+    assert_eq!(format!("{:?}", Foo([0; 4])), "Foo([..; 4])");
+    assert_eq!(format!("{:#?}", Foo([0; 4])), "Foo(\n    [..; 4]\n)");
+    assert_eq!(format!("{:?}", Computed([0; 16])), "Computed([..; 16])");
+    assert_eq!(format!("{:?}", Generic([(); 3])), "Generic([..; 3])");
+    assert_eq!(format!("{:?}", Packet::Header([0; 2])), "Packet::Header([..; 2])");
+    assert_eq!(format!("{:?}", Packet::Payload(vec![])), "Packet::Payload(..)");
+    assert_eq!(format!("{:?}", Packet::Pair([0; 2], 1)), "Packet::Pair(..)");
+}
+
+#[test]
+fn case() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(case = "snake")]
+    enum Snake {
+        HttpRequest(u8),
+        HTTPResponse { code: u16 },
+        #[shallow_debug(rename = "KeepMe")]
+        Renamed,
+        Single,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(case = "kebab")]
+    enum Kebab {
+        HttpRequest,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(case = "camel")]
+    #[allow(non_camel_case_types)]
+    enum Camel {
+        HttpRequest,
+        snake_case_name,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(case = "pascal")]
+    #[allow(non_camel_case_types)]
+    enum Pascal {
+        http_request,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(case = "screaming_snake", name_fn)]
+    enum Screaming {
+        HttpRequest2,
+    }
+
+    assert_eq!(format!("{:?}", Snake::HttpRequest(1)), "Snake::http_request(..)");
+    assert_eq!(format!("{:?}", Snake::HTTPResponse { code: 1 }), "Snake::http_response{..}");
+    assert_eq!(format!("{:?}", Snake::Renamed), "Snake::KeepMe");
+    assert_eq!(format!("{:?}", Snake::Single), "Snake::single");
+    assert_eq!(format!("{:?}", Kebab::HttpRequest), "Kebab::http-request");
+    assert_eq!(format!("{:?}", Camel::HttpRequest), "Camel::httpRequest");
+    assert_eq!(format!("{:?}", Camel::snake_case_name), "Camel::snakeCaseName");
+    assert_eq!(format!("{:?}", Pascal::http_request), "Pascal::HttpRequest");
+    assert_eq!(format!("{:?}", Screaming::HttpRequest2), "Screaming::HTTP_REQUEST2");
+    assert_eq!(Screaming::HttpRequest2.shallow_name(), "HTTP_REQUEST2");
+}
+
+#[test]
+fn size() {
+    use std::mem::size_of;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(size)]
+    #[repr(C)]
+    struct Pair {
+        a: u32,
+        b: u32,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(size, field_names)]
+    enum Message {
+        Data([u8; 24]),
+        Header { id: u16 },
+        Empty,
+        #[shallow_debug(skip)]
+        Hidden(u8),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(size, show_field_count, elision = "...")]
+    struct Counted(u8, [u64; 2]);
+
+    let size = size_of::<Message>();
+    assert_eq!(format!("{:?}", Pair { a: 1, b: 2 }), "Pair{.. /*8B*/}");
+    assert_eq!(format!("{:#?}", Pair { a: 1, b: 2 }), "Pair {\n    .. /*8B*/\n}");
+    assert_eq!(format!("{:?}", Message::Data([0; 24])), format!("Message::Data(.. /*{size}B*/)"));
+    assert_eq!(
+        format!("{:?}", Message::Header { id: 1 }),
+        format!("Message::Header {{ id, .. /*{size}B*/ }}"),
+    );
+    assert_eq!(format!("{:?}", Message::Empty), format!("Message::Empty /*{size}B*/"));
+    assert_eq!(format!("{:?}", Message::Hidden(1)), format!("Message::.. /*{size}B*/"));
+    assert_eq!(format!("{:?}", Counted(1, [0; 2])), "Counted(...2 /*24B*/)");
+}
+
+#[test]
+fn variant_index() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_index, to_string_fn)]
+    enum Stage {
+        Start,
+        Running { step: u32 },
+        Done(bool),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_index, builder)]
+    enum Shown {
+        A(#[shallow_debug(show)] u8),
+        #[shallow_debug(skip)]
+        B,
+        C { id: u8 },
+    }
+
+    assert_eq!(format!("{:?}", Stage::Start), "Stage::Start [0/3]");
+    assert_eq!(format!("{:?}", Stage::Running { step: 1 }), "Stage::Running{..} [1/3]");
+    assert_eq!(format!("{:?}", Stage::Done(true)), "Stage::Done(..) [2/3]");
+    assert_eq!(format!("{:#?}", Stage::Done(true)), "Stage::Done(\n    ..\n) [2/3]");
+    assert_eq!(Stage::Running { step: 1 }.shallow_to_string(), "Stage::Running{..} [1/3]");
+    assert_eq!(format!("{:?}", Shown::A(7)), "Shown::A(7) [0/3]");
+    assert_eq!(format!("{:?}", Shown::B), "Shown::..");
+    assert_eq!(format!("{:?}", Shown::C { id: 1 }), "Shown::C { .. } [2/3]");
+}
+
+#[test]
+fn unrelated_attributes() {
+    // Only `shallow_debug` attributes are read, whatever comes before or after the derive, so tool
+    // attributes and the ones left for other macros are ignored.
+    #[rustfmt::skip]
+    #[must_use]
+    #[derive(Clone, ShallowDebug)]
+    #[cfg_attr(all(), derive(Copy))]
+    #[doc(hidden)]
+    #[shallow_debug(field_names)]
+    #[allow(clippy::all)]
+    #[repr(u8)]
+    enum Tagged {
+        #[allow(unused)]
+        #[deprecated = "use `B`"]
+        A { #[doc = "The id."] id: u8 } = 1,
+        #[doc(alias = "bee")]
+        B,
+    }
+
+    #[derive(ShallowDebug)]
+    #[non_exhaustive]
+    struct Marker;
+
+    #[allow(deprecated)]
+    let value = Tagged::A { id: 1 };
+    assert_eq!(format!("{value:?}"), "Tagged::A { id, .. }");
+    assert_eq!(format!("{:?}", Tagged::B), "Tagged::B");
+    assert_eq!(format!("{:?}", Marker), "Marker");
+}
+
+#[test]
+fn separator() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(separator = ".", prefix = "net", name_fn, to_string_fn)]
+    enum State {
+        Idle,
+        Busy(u8),
+        #[shallow_debug(skip)]
+        Hidden,
+        #[shallow_debug(variant_only)]
+        Bare { id: u8 },
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(separator = " / ", inline_generics)]
+    enum Generic<T> {
+        A(T),
+        #[shallow_debug(skip)]
+        B(T),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(separator = ".")]
+    struct Plain(u8);
+
+    assert_eq!(format!("{:?}", State::Idle), "net::State.Idle");
+    assert_eq!(format!("{:?}", State::Busy(1)), "net::State.Busy(..)");
+    // Skipped variants are still printed after `::`, so that `..` can't be mistaken for an elision
+    // of the name.
+    assert_eq!(format!("{:?}", State::Hidden), "net::State::..");
+    assert_eq!(State::Hidden.shallow_to_string(), "net::State::..");
+    assert_eq!(format!("{:?}", Generic::B(1u8)), "Generic::<u8>::..");
+    assert_eq!(format!("{:?}", State::Bare { id: 1 }), "Bare{..}");
+    assert_eq!(State::Busy(1).shallow_name(), "Busy");
+    assert_eq!(format!("{:?}", Generic::A(1u8)), "Generic::<u8> / A(..)");
+    assert_eq!(format!("{:?}", Plain(1)), "Plain(..)");
+}
+
+#[test]
+fn letter_case() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(lowercase, name_fn)]
+    enum Lower {
+        HttpRequest(u8),
+        #[shallow_debug(rename = "KeepMe")]
+        Renamed,
+        #[shallow_debug(skip)]
+        Hidden,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(uppercase, prefix = "net", field_names)]
+    struct Upper {
+        id: u32,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(uppercase, case = "kebab", variant_only, variants_const)]
+    enum Kebab {
+        HttpRequest,
+    }
+
+    assert_eq!(format!("{:?}", Lower::HttpRequest(1)), "lower::httprequest(..)");
+    assert_eq!(format!("{:?}", Lower::Renamed), "lower::keepme");
+    assert_eq!(format!("{:?}", Lower::Hidden), "lower::..");
+    assert_eq!(Lower::Renamed.shallow_name(), "keepme");
+    assert_eq!(format!("{:?}", Upper { id: 1 }), "NET::UPPER { id, .. }");
+    assert_eq!(format!("{:?}", Kebab::HttpRequest), "HTTP-REQUEST");
+    assert_eq!(Kebab::VARIANTS, ["HTTP-REQUEST"]);
+}
+
+#[test]
+fn variant_only() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_only)]
+    enum Message {
+        Data(u8),
+        Close { code: u16 },
+        Ping,
+        #[shallow_debug(skip)]
+        Secret,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_only)]
+    struct Unaffected(u8);
+
+    assert_eq!(format!("{:?}", Message::Data(1)), "Data(..)");
+    assert_eq!(format!("{:?}", Message::Close { code: 1 }), "Close{..}");
+    assert_eq!(format!("{:#?}", Message::Close { code: 1 }), "Close {\n    ..\n}");
+    assert_eq!(format!("{:?}", Message::Ping), "Ping");
+    assert_eq!(format!("{:?}", Message::Secret), "..");
+    assert_eq!(format!("{:?}", Unaffected(1)), "Unaffected(..)");
+}
+
+#[test]
+fn with() {
+    use core::fmt;
+
+    fn fmt_len<T>(list: &List<T>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "List(len = {})", list.items.len())
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(with = fmt_len)]
+    struct List<T> {
+        items: Vec<T>,
+    }
+
+    fn fmt_state(state: &State, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if matches!(state, State::On) { "on" } else { "off" })
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(with = fmt_state)]
+    enum State {
+        On,
+        Off,
+    }
+
+    mod inner {
+        use core::fmt;
+
+        pub fn fmt_opaque<T>(_: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("<opaque>")
+        }
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(with = inner::fmt_opaque)]
+    struct Opaque(u8);
+
+    struct NotDebug;
+
+    let list = List { items: vec![NotDebug, NotDebug] };
+    assert_eq!(format!("{list:?}"), "List(len = 2)");
+    assert_eq!(format!("{:?}", State::On), "on");
+    assert_eq!(format!("{:?}", State::Off), "off");
+    assert_eq!(format!("{:?}", Opaque(1)), "<opaque>");
+}
+
+#[test]
+fn variants_const() {
+    #[derive(ShallowDebug, ShallowDisplay)]
+    #[shallow_debug(variants_const, name_fn, case = "kebab")]
+    enum Level<T> {
+        Trace,
+        DebugInfo(T),
+        #[shallow_debug(rename = "WARN")]
+        Warn { message: T },
+        #[cfg(any())]
+        Never,
+        #[shallow_debug(skip)]
+        Hidden,
+        Error,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variants_const)]
+    enum Empty {}
+
+    assert_eq!(Level::<()>::VARIANTS, ["trace", "debug-info", "WARN", "..", "error"]);
+    assert_eq!(Level::DebugInfo(1).shallow_name(), Level::<u8>::VARIANTS[1]);
+    assert!(Empty::VARIANTS.is_empty());
+}
+
+#[test]
+fn same_variant() {
+    struct NotPartialEq(u8);
+
+    #[derive(ShallowDebug, ShallowDisplay)]
+    #[shallow_debug(same_variant)]
+    enum Event {
+        Click(NotPartialEq),
+        Key { code: NotPartialEq },
+        Close,
+    }
+
+    let click = Event::Click(NotPartialEq(1));
+    assert!(click.same_variant(&Event::Click(NotPartialEq(2))));
+    assert!(!click.same_variant(&Event::Key { code: NotPartialEq(1) }));
+    assert!(!click.same_variant(&Event::Close));
+    assert!(Event::Close.same_variant(&Event::Close));
+}
+
+#[test]
+fn special_characters() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "{}", prefix = "a{b}")]
+    enum Braces {
+        #[shallow_debug(rename = "{0} %s {x:?}")]
+        Tuple(u8),
+        #[shallow_debug(rename = "}}")]
+        Named { a: u8 },
+        #[shallow_debug(rename = "{")]
+        Unit,
+        #[shallow_debug(skip)]
+        Skipped,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "%d{", elision = "{..}")]
+    struct Elided(u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "{}")]
+    union Union {
+        a: u8,
+    }
+
+    assert_eq!(format!("{:?}", Braces::Tuple(1)), "a{b}::{}::{0} %s {x:?}(..)");
+    assert_eq!(format!("{:?}", Braces::Named { a: 1 }), "a{b}::{}::}}{..}");
+    assert_eq!(format!("{:#?}", Braces::Named { a: 1 }), "a{b}::{}::}} {\n    ..\n}");
+    assert_eq!(format!("{:?}", Braces::Unit), "a{b}::{}::{");
+    assert_eq!(format!("{:?}", Braces::Skipped), "a{b}::{}::..");
+    assert_eq!(format!("{:?}", Elided(1)), "%d{({..})");
+    assert_eq!(format!("{:?}", Union { a: 1 }), "{}");
+}
+
+#[test]
+fn repr() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(repr = u8)]
+    enum Opcode {
+        Ping = 1,
+        Pong,
+        Data = 0x10,
+        #[shallow_debug(skip)]
+        Reserved = 0xff,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(repr = i32, builder)]
+    enum Implicit {
+        First,
+        Second,
+    }
+
+    assert_eq!(format!("{:?}", Opcode::Ping), "Opcode::Ping(1)");
+    assert_eq!(format!("{:?}", Opcode::Pong), "Opcode::Pong(2)");
+    assert_eq!(format!("{:?}", Opcode::Data), "Opcode::Data(16)");
+    assert_eq!(format!("{:?}", Opcode::Reserved), "Opcode::..");
+    assert_eq!(format!("{:?}", Implicit::First), "Implicit::First(0)");
+    assert_eq!(format!("{:#?}", Implicit::Second), "Implicit::Second(\n    1,\n)");
+}
+
+#[test]
+fn union_field() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(union_field = "tag")]
+    union Value {
+        tag: u8,
+        raw: u64,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(union_field = "type", rename = "Raw", elision = "?")]
+    union Keyword {
+        r#type: u8,
+    }
+
+    assert_eq!(format!("{:?}", Value { raw: 1 }), "Value { tag: .. }");
+    assert_eq!(format!("{:#?}", Value { tag: 1 }), "Value {\n    tag: ..,\n}");
+    assert_eq!(format!("{:?}", Keyword { r#type: 1 }), "Raw { type: ? }");
+}
+
+#[test]
+fn format_specifiers_in_names() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "{:?}{0}", array_len, elision = "{}")]
+    struct Array([u8; 2]);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "{x}", repr = u8)]
+    enum Repr {
+        #[shallow_debug(rename = "{{}}")]
+        A = 1,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "{}}", inline_generics, field_names, elision = "{:>5}")]
+    struct Generic<T> {
+        value: T,
+    }
+
+    assert_eq!(format!("{:?}", Array([0; 2])), "{:?}{0}([{}; 2])");
+    assert_eq!(format!("{:#?}", Array([0; 2])), "{:?}{0}(\n    [{}; 2]\n)");
+    assert_eq!(format!("{:?}", Repr::A), "{x}::{{}}(1)");
+    assert_eq!(format!("{:?}", Generic { value: 1u8 }), "{}}::<u8> { value, {:>5} }");
+}
+
+#[test]
+fn inline() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline(always))]
+    enum Always {
+        A(u8),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline(never))]
+    struct Never(u8);
+
+    assert_eq!(format!("{:?}", Always::A(1)), "Always::A(..)");
+    assert_eq!(format!("{:?}", Never(1)), "Never(..)");
+}
+
+#[test]
+fn format_template() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(format = "[{name}]{fields}")]
+    enum Event {
+        Click { x: i32 },
+        Key(char),
+        Close,
+        #[shallow_debug(skip)]
+        Internal(u8),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(format = "<{fields} of {name}>", field_names, elision = "...")]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(format = "{{{name}}}: {fields}", show_field_count)]
+    struct Pair(u8, u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(format = "union {name}")]
+    union Raw {
+        int: u32,
+    }
+
+    assert_eq!(format!("{:?}", Event::Click { x: 1 }), "[Event::Click]{..}");
+    assert_eq!(format!("{:#?}", Event::Click { x: 1 }), "[Event::Click] {\n    ..\n}");
+    assert_eq!(format!("{:?}", Event::Key('a')), "[Event::Key](..)");
+    assert_eq!(format!("{:?}", Event::Close), "[Event::Close]");
+    assert_eq!(format!("{:?}", Event::Internal(1)), "[Event::..]");
+    assert_eq!(format!("{:?}", Point { x: 1, y: 2 }), "< { x, y, ... } of Point>");
+    assert_eq!(format!("{:?}", Pair(1, 2)), "{Pair}: (..2)");
+    assert_eq!(format!("{:?}", Raw { int: 1 }), "union Raw");
+}
+
+#[test]
+fn skip_type_name() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(skip_type_name, field_names)]
+    struct User {
+        id: u32,
+        name: String,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(skip_type_name, field_names)]
+    enum Request {
+        Login { user: String, password: String },
+        Fetch(u32),
+        Logout,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(skip_type_name)]
+    struct Plain(u8, u8);
+
+    let user = User { id: 1, name: "a".into() };
+    assert_eq!(format!("{user:?}"), "{ id, name, .. }");
+    assert_eq!(format!("{user:#?}"), "{\n    id,\n    name,\n    ..\n}");
+    let login = Request::Login { user: "a".into(), password: "b".into() };
+    assert_eq!(format!("{login:?}"), "Login { user, password, .. }");
+    assert_eq!(format!("{:?}", Request::Fetch(1)), "Fetch(..)");
+    assert_eq!(format!("{:?}", Request::Logout), "Logout");
+    assert_eq!(format!("{:?}", Plain(1, 2)), "(..)");
+}
+
+#[test]
+fn bare_attribute() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug]
+    enum E {
+        #[shallow_debug]
+        A(#[shallow_debug] u8),
+    }
+
+    assert_eq!(format!("{:?}", E::A(1)), "E::A(..)");
+}
+
+#[test]
+fn to_string_fn() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(to_string_fn, field_names)]
+    enum Event<T> {
+        Click { x: T, y: T },
+        Close,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(to_string_fn, transparent)]
+    struct Id<T>(T);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(to_string_fn)]
+    enum Shown {
+        A(#[shallow_debug(show)] u8),
+        B,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(to_string_fn, skip_type_name, format = "<{name}>{fields}")]
+    enum Formatted {
+        A { x: u8 },
+        #[shallow_debug(skip)]
+        B(u8),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(to_string_fn)]
+    enum Never {}
+
+    // The output is known for every value, so the text is returned without allocating.
+    let click = Event::Click { x: 1, y: 2 };
+    let text: &'static str = click.shallow_to_string();
+    assert_eq!(text, format!("{click:?}"));
+    assert_eq!(text, "Event::Click { x, y, .. }");
+    assert_eq!(Event::<u8>::Close.shallow_to_string(), "Event::Close");
+    let text: &'static str = Formatted::A { x: 1 }.shallow_to_string();
+    assert_eq!(text, "<A>{..}");
+    assert_eq!(Formatted::B(1).shallow_to_string(), "<..>");
+    assert_eq!(Formatted::B(1).shallow_to_string(), format!("{:?}", Formatted::B(1)));
+
+    // Otherwise the output is formatted into a `String`. The method is then only available when
+    // the `Debug` impl applies.
+    let text: String = Id(1).shallow_to_string();
+    assert_eq!(text, "1");
+    let text: String = Shown::B.shallow_to_string();
+    assert_eq!(text, "Shown::B");
+    assert_eq!(Shown::A(1).shallow_to_string(), "Shown::A(1)");
+}
+
+#[test]
+fn write_fn() {
+    use std::fmt::Write;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(write_fn, field_names)]
+    enum Event<W> {
+        Click { x: W, y: W },
+        Close,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(write_fn)]
+    struct Shown {
+        #[shallow_debug(show)]
+        id: u8,
+        secret: u8,
+    }
+
+    /// A `fmt::Write` that isn't a `String`, and that fails when it is full.
+    struct Buffer {
+        bytes: [u8; 16],
+        len: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            let end = self.len + s.len();
+            self.bytes.get_mut(self.len..end).ok_or(std::fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut text = String::from("event: ");
+    Event::Click { x: 1, y: 2 }.shallow_fmt(&mut text).unwrap();
+    assert_eq!(text, "event: Event::Click { x, y, .. }");
+
+    let mut text = String::new();
+    Shown { id: 7, secret: 1 }.shallow_fmt(&mut text).unwrap();
+    assert_eq!(text, "Shown { id: 7, .. }");
+
+    let mut buffer = Buffer { bytes: [0; 16], len: 0 };
+    Event::<u8>::Close.shallow_fmt(&mut buffer).unwrap();
+    assert_eq!(&buffer.bytes[..buffer.len], b"Event::Close");
+    assert!(Event::Click { x: 1, y: 2 }.shallow_fmt(&mut buffer).is_err());
+
+    // The writer can be unsized.
+    let writer: &mut dyn Write = &mut String::new();
+    Shown { id: 7, secret: 1 }.shallow_fmt(writer).unwrap();
+}
+
+#[test]
+fn variant_only_per_variant() {
+    #[derive(ShallowDebug)]
+    enum Prefixed {
+        A(u8),
+        #[shallow_debug(variant_only)]
+        B { x: u8 },
+        #[shallow_debug(variant_only = false)]
+        C,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_only)]
+    enum Bare {
+        A(u8),
+        #[shallow_debug(variant_only = false)]
+        B { x: u8 },
+        #[shallow_debug(variant_only = false, skip)]
+        C,
+    }
+
+    assert_eq!(format!("{:?}", Prefixed::A(1)), "Prefixed::A(..)");
+    assert_eq!(format!("{:?}", Prefixed::B { x: 1 }), "B{..}");
+    assert_eq!(format!("{:?}", Prefixed::C), "Prefixed::C");
+    assert_eq!(format!("{:?}", Bare::A(1)), "A(..)");
+    assert_eq!(format!("{:?}", Bare::B { x: 1 }), "Bare::B{..}");
+    assert_eq!(format!("{:?}", Bare::C), "Bare::..");
+}
+
+mod names {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    pub static FRENCH: AtomicBool = AtomicBool::new(false);
+
+    pub fn printer() -> &'static str {
+        if FRENCH.load(Ordering::Relaxed) {
+            "Imprimante"
+        } else {
+            "Printer"
+        }
+    }
+
+    pub fn state(state: &super::State) -> &'static str {
+        match (state, FRENCH.load(Ordering::Relaxed)) {
+            (super::State::Idle, false) => "Idle",
+            (super::State::Idle, true) => "Inactif",
+            (super::State::Printing { .. }, false) => "Printing",
+            (super::State::Printing { .. }, true) => "Impression",
+            (super::State::Failed(..), _) => "?",
+        }
+    }
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_with = "names::printer", field_names)]
+struct Printer {
+    queue: Vec<String>,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_with = "names::state", field_names)]
+enum State {
+    Idle,
+    Printing { page: u32 },
+    #[shallow_debug(rename = "Failed")]
+    Failed(String),
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_with = "names::printer", builder)]
+struct Job(u32);
+
+#[test]
+fn name_with() {
+    use std::sync::atomic::Ordering;
+
+    // Both languages are checked in the same test, so that no other test sees the flag set.
+    let printer = Printer { queue: Vec::new() };
+    assert_eq!(format!("{printer:?}"), "Printer { queue, .. }");
+    assert_eq!(format!("{:?}", State::Idle), "State::Idle");
+    assert_eq!(format!("{:?}", State::Printing { page: 1 }), "State::Printing { page, .. }");
+    assert_eq!(format!("{:?}", State::Failed("jam".into())), "State::Failed(..)");
+    assert_eq!(format!("{:#?}", Job(1)), "Printer(\n    ..,\n)");
+    names::FRENCH.store(true, Ordering::Relaxed);
+    assert_eq!(format!("{printer:?}"), "Imprimante { queue, .. }");
+    assert_eq!(format!("{:?}", State::Idle), "State::Inactif");
+    assert_eq!(format!("{:?}", State::Printing { page: 1 }), "State::Impression { page, .. }");
+    assert_eq!(format!("{:?}", Job(1)), "Imprimante(..)");
+    names::FRENCH.store(false, Ordering::Relaxed);
+}
+
+mod reexported {
+    mod imp {
+        use shallow_debug::ShallowDebug;
+
+        // Known as `Handle` outside of this module, while the code refers to it as `RawHandle`.
+        #[derive(ShallowDebug)]
+        #[shallow_debug(rename = "Handle", name_fn, same_variant)]
+        pub enum RawHandle {
+            File(i32),
+            Socket { fd: i32 },
+        }
+    }
+
+    pub use imp::RawHandle as Handle;
+}
+
+#[test]
+fn rename_reexport() {
+    use reexported::Handle;
+
+    let file = Handle::File(1);
+    assert_eq!(format!("{file:?}"), "Handle::File(..)");
+    assert_eq!(format!("{:?}", Handle::Socket { fd: 1 }), "Handle::Socket{..}");
+    assert_eq!(file.shallow_name(), "File");
+    assert!(file.same_variant(&Handle::File(2)));
+}
+
+#[test]
+fn attribute_lists() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug()]
+    #[shallow_debug(rename = "Lists",)]
+    #[shallow_debug(
+        field_names,
+        variant_only,
+    )]
+    enum Listed {
+        #[shallow_debug()]
+        Empty(u8),
+        #[shallow_debug(rename = "Renamed")]
+        #[shallow_debug(variant_only = false,)]
+        Split {
+            #[shallow_debug(show,)]
+            id: u8,
+            data: u8,
+        },
+        #[shallow_debug(skip,)]
+        Skipped,
+    }
+
+    assert_eq!(format!("{:?}", Listed::Empty(1)), "Empty(..)");
+    assert_eq!(format!("{:?}", Listed::Split { id: 1, data: 2 }), "Lists::Renamed { id: 1, .. }");
+    assert_eq!(format!("{:?}", Listed::Skipped), "..");
+}
+
+#[test]
+fn unit_suffix() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(unit_suffix = "()")]
+    struct Marker;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(unit_suffix = "{}")]
+    enum Shape {
+        Empty,
+        Tuple(u8),
+        Parens(),
+        Braces {},
+        #[shallow_debug(skip)]
+        Skipped,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(unit_suffix = "", size)]
+    struct Sized;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(unit_suffix = "()", format = "<{name}{fields}>", to_string_fn)]
+    enum Templated {
+        A,
+        B(u8),
+    }
+
+    assert_eq!(format!("{:?}", Marker), "Marker()");
+    assert_eq!(format!("{:#?}", Marker), "Marker()");
+    assert_eq!(format!("{:?}", Shape::Empty), "Shape::Empty{}");
+    assert_eq!(format!("{:?}", Shape::Tuple(1)), "Shape::Tuple(..)");
+    assert_eq!(format!("{:?}", Shape::Parens()), "Shape::Parens");
+    assert_eq!(format!("{:?}", Shape::Braces {}), "Shape::Braces");
+    assert_eq!(format!("{:?}", Shape::Skipped), "Shape::..");
+    assert_eq!(format!("{:?}", Sized), "Sized /*0B*/");
+    assert_eq!(Templated::A.shallow_to_string(), "<Templated::A()>");
+    assert_eq!(format!("{:?}", Templated::B(1)), "<Templated::B(..)>");
+}
+
+#[test]
+fn name_with_shown_tuple() {
+    fn wrapped() -> &'static str {
+        "Named"
+    }
+
+    fn event(_: &Event) -> &'static str {
+        "Variant"
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_with = "wrapped")]
+    struct Wrapped(#[shallow_debug(show)] u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_with = "event")]
+    enum Event {
+        One(#[shallow_debug(show)] u8),
+        Two(#[shallow_debug(show)] u8, u8),
+    }
+
+    assert_eq!(format!("{:?}", Wrapped(1)), "Named(1)");
+    assert_eq!(format!("{:#?}", Wrapped(1)), "Named(\n    1,\n)");
+    assert_eq!(format!("{:?}", Event::One(1)), "Event::Variant(1)");
+    assert_eq!(format!("{:#?}", Event::One(1)), "Event::Variant(\n    1,\n)");
+    assert_eq!(format!("{:?}", Event::Two(1, 2)), "Event::Variant(1, ..)");
+    // The formatting flags are passed on to the shown field.
+    assert_eq!(format!("{:05?}", Wrapped(1)), "Named(00001)");
+}
+
+#[test]
+fn name_with_prefix() {
+    fn dyn_name() -> &'static str {
+        "Dyn"
+    }
+
+    fn variant(_: &Renamed) -> &'static str {
+        "Variant"
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(prefix = "pre", name_with = "dyn_name")]
+    struct Prefixed(u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(prefix = "pre", name_with = "dyn_name", field_names)]
+    struct Fields {
+        a: u8,
+    }
+
+    // The name of an enum is still printed before the one of the variant, renamed or not.
+    #[derive(ShallowDebug)]
+    #[shallow_debug(rename = "Other", prefix = "pre", name_with = "variant")]
+    enum Renamed {
+        A(u8),
+    }
+
+    assert_eq!(format!("{:?}", Prefixed(1)), "pre::Dyn(..)");
+    assert_eq!(format!("{:?}", Fields { a: 1 }), "pre::Dyn { a, .. }");
+    assert_eq!(format!("{:?}", Renamed::A(1)), "pre::Other::Variant(..)");
+}
@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+mod external {
+    pub enum Gated {
+        Always,
+        #[cfg(feature = "full-debug")]
+        WithFeature(u8),
+        #[cfg(not(feature = "full-debug"))]
+        WithoutFeature { value: u8 },
+    }
+}
+
+shallow_debug::shallow_debug!(external::Gated {
+    Always,
+    #[cfg(feature = "full-debug")]
+    WithFeature(..),
+    #[cfg(not(feature = "full-debug"))]
+    WithoutFeature { .. },
+    #[cfg(any())]
+    Never,
+});
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_fn)]
+enum Local {
+    Always,
+    #[cfg(feature = "full-debug")]
+    WithFeature(u8),
+    #[cfg(not(feature = "full-debug"))]
+    WithoutFeature { value: u8 },
+    #[cfg(any())]
+    Never,
+}
+
+#[test]
+fn foreign_enabled_variants() {
+    use external::Gated;
+
+    assert_eq!(format!("{:?}", Gated::Always), "Gated::Always");
+    #[cfg(feature = "full-debug")]
+    assert_eq!(format!("{:?}", Gated::WithFeature(1)), "Gated::WithFeature(..)");
+    #[cfg(not(feature = "full-debug"))]
+    assert_eq!(format!("{:?}", Gated::WithoutFeature { value: 1 }), "Gated::WithoutFeature{..}");
+}
+
+#[test]
+fn derived_enabled_variants() {
+    assert_eq!(format!("{:?}", Local::Always), "Local::Always");
+    #[cfg(feature = "full-debug")]
+    assert_eq!(Local::WithFeature(1).shallow_name(), "WithFeature");
+    #[cfg(not(feature = "full-debug"))]
+    assert_eq!(Local::WithoutFeature { value: 1 }.shallow_name(), "WithoutFeature");
+}
+
+// The compiler removes disabled fields, and expands `cfg_attr`, before the derive sees the type,
+// so the shown fields never refer to fields that don't exist.
+#[derive(ShallowDebug)]
+#[shallow_debug(builder)]
+struct Config {
+    #[shallow_debug(show)]
+    name: &'static str,
+    #[cfg(feature = "full-debug")]
+    #[shallow_debug(show)]
+    verbose: bool,
+    #[cfg_attr(feature = "full-debug", shallow_debug(show))]
+    level: u8,
+    #[cfg(not(feature = "full-debug"))]
+    hidden: u8,
+}
+
+#[derive(ShallowDebug)]
+enum Command {
+    Run {
+        #[shallow_debug(show)]
+        id: u8,
+        #[cfg(feature = "full-debug")]
+        #[shallow_debug(show)]
+        trace: bool,
+    },
+    Pair(#[shallow_debug(show)] u8, #[cfg(feature = "full-debug")] #[shallow_debug(show)] u8),
+}
+
+#[test]
+fn shown_fields_behind_cfg() {
+    #[cfg(feature = "full-debug")]
+    {
+        let config = Config { name: "a", verbose: true, level: 1 };
+        assert_eq!(format!("{config:?}"), r#"Config { name: "a", verbose: true, level: 1 }"#);
+        let run = Command::Run { id: 1, trace: false };
+        assert_eq!(format!("{run:?}"), "Command::Run { id: 1, trace: false }");
+        assert_eq!(format!("{:?}", Command::Pair(1, 2)), "Command::Pair(1, 2)");
+    }
+    #[cfg(not(feature = "full-debug"))]
+    {
+        let config = Config { name: "a", level: 1, hidden: 2 };
+        assert_eq!(format!("{config:?}"), r#"Config { name: "a", .. }"#);
+        assert_eq!(format!("{:?}", Command::Run { id: 1 }), "Command::Run { id: 1 }");
+        assert_eq!(format!("{:?}", Command::Pair(1)), "Command::Pair(1)");
+    }
+}
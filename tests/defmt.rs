@@ -0,0 +1,65 @@
+#![cfg(feature = "defmt")]
+#![allow(dead_code)]
+
+use shallow_debug::{ShallowDebug, ShallowDefmt};
+
+/// The parts of the `defmt` API used by the generated impls, with the same signatures. The real
+/// formatter encodes the output for a host to decode, this one collects the written strings.
+mod defmt {
+    use std::cell::RefCell;
+
+    pub struct Formatter<'a> {
+        pub(crate) output: &'a RefCell<String>,
+    }
+
+    pub trait Format {
+        fn format(&self, fmt: Formatter<'_>);
+    }
+
+    macro_rules! write {
+        ($fmt:expr, "{=str}", $value:expr) => {
+            $fmt.output.borrow_mut().push_str($value)
+        };
+    }
+    pub(crate) use write;
+
+    /// What a host would decode from `defmt::info!("{}", value)`.
+    pub fn to_string(value: &impl Format) -> String {
+        let output = RefCell::new(String::new());
+        value.format(Formatter { output: &output });
+        output.into_inner()
+    }
+}
+
+#[derive(ShallowDefmt)]
+enum Event<T> {
+    Click { x: i32, y: i32 },
+    Key(char),
+    #[shallow_debug(rename = "Idle")]
+    Nothing,
+    #[shallow_debug(skip)]
+    Internal(T),
+}
+
+#[derive(ShallowDebug, ShallowDefmt)]
+#[shallow_debug(name_fn, show_field_count)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn defmt() {
+    assert_eq!(defmt::to_string(&Event::<u8>::Click { x: 1, y: 2 }), "Event::Click{..}");
+    assert_eq!(defmt::to_string(&Event::<u8>::Key('a')), "Event::Key(..)");
+    assert_eq!(defmt::to_string(&Event::<u8>::Nothing), "Event::Idle");
+    assert_eq!(defmt::to_string(&Event::Internal(1)), "Event::..");
+}
+
+#[test]
+fn alongside_debug() {
+    let point = Point { x: 1, y: 2 };
+    assert_eq!(defmt::to_string(&point), "Point{..2}");
+    assert_eq!(format!("{point:?}"), "Point{..2}");
+    assert_eq!(point.shallow_name(), "Point");
+}
@@ -0,0 +1,47 @@
+#![deny(deprecated)]
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_fn, variants_const)]
+enum Message {
+    Ping,
+    #[deprecated]
+    Old(u8),
+    #[deprecated]
+    Older { id: u8 },
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug)]
+enum Full {
+    #[deprecated]
+    Old(u8),
+}
+
+#[deprecated]
+#[derive(ShallowDebug)]
+struct OldStruct(u8);
+
+mod generated {
+    pub enum Foreign {
+        Ping,
+        #[deprecated]
+        Old(u8),
+    }
+}
+
+shallow_debug::shallow_debug!(generated::Foreign { Ping, Old(..) });
+
+// The generated code doesn't warn about the deprecated items, but the uses written here still do.
+#[test]
+fn deprecated() {
+    assert_eq!(format!("{:?}", Message::Ping), "Message::Ping");
+    assert_eq!(Message::VARIANTS, ["Ping", "Old", "Older"]);
+    assert_eq!(format!("{:?}", generated::Foreign::Ping), "Foreign::Ping");
+    #[allow(deprecated)]
+    let old = Message::Old(1);
+    assert_eq!(format!("{old:?}"), "Message::Old(..)");
+    assert_eq!(old.shallow_name(), "Old");
+}
@@ -0,0 +1,55 @@
+#![allow(dead_code)]
+
+use shallow_debug::{ShallowDebug, ShallowDisplay};
+
+#[test]
+fn display() {
+    #[derive(ShallowDisplay)]
+    enum MyEnum<T> {
+        A(T),
+        B { value: T },
+        C,
+    }
+
+    assert_eq!(format!("{}", MyEnum::A(1)), "MyEnum::A(..)");
+    assert_eq!(format!("{}", MyEnum::B { value: 1 }), "MyEnum::B{..}");
+    assert_eq!(format!("{}", MyEnum::<i32>::C), "MyEnum::C");
+}
+
+#[test]
+fn display_alongside_debug() {
+    #[derive(ShallowDebug, ShallowDisplay)]
+    #[shallow_debug(rename = "Renamed")]
+    struct Both(u8);
+
+    assert_eq!(format!("{}", Both(1)), "Renamed(..)");
+    assert_eq!(format!("{:?}", Both(1)), "Renamed(..)");
+}
+
+#[test]
+fn display_transparent() {
+    #[derive(ShallowDisplay)]
+    #[shallow_debug(transparent)]
+    struct Name<T>(T);
+
+    assert_eq!(format!("{}", Name("text")), "text");
+}
+
+#[test]
+fn helper_attributes_without_debug() {
+    // Each derive registers the helper attribute, so it can be used on variants and fields without
+    // `ShallowDebug`.
+    #[derive(ShallowDisplay)]
+    enum Event {
+        #[shallow_debug(skip)]
+        Internal,
+        Click {
+            #[shallow_debug(show)]
+            x: i32,
+            y: i32,
+        },
+    }
+
+    assert_eq!(Event::Internal.to_string(), "Event::..");
+    assert_eq!(Event::Click { x: 1, y: 2 }.to_string(), "Event::Click { x: 1, .. }");
+}
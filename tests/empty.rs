@@ -0,0 +1,65 @@
+#![allow(dead_code)]
+
+use shallow_debug::{ShallowDebug, ShallowDisplay};
+
+#[derive(ShallowDebug, ShallowDisplay)]
+#[shallow_debug(name_fn)]
+enum Never {}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug)]
+enum FullNever {}
+
+#[derive(ShallowDebug)]
+enum Single {
+    Only(u8),
+}
+
+fn format_never(never: &Never) -> String {
+    format!("{never:?}")
+}
+
+#[test]
+fn uninhabited() {
+    let _: fn(&Never) -> String = format_never;
+    let _ = |never: &FullNever| format!("{never:?}");
+    let _ = |never: &Never| never.shallow_name();
+}
+
+#[test]
+fn single_variant() {
+    assert_eq!(format!("{:?}", Single::Only(1)), "Single::Only(..)");
+}
+
+#[test]
+fn no_fields() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(full_debug)]
+    enum E {
+        Tuple(),
+        Named {},
+        Unit,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_field_count)]
+    struct Tuple();
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(builder)]
+    struct Named {}
+
+    #[cfg(not(feature = "full-debug"))]
+    {
+        assert_eq!(format!("{:?}", E::Tuple()), "E::Tuple");
+        assert_eq!(format!("{:#?}", E::Named {}), "E::Named");
+    }
+    #[cfg(feature = "full-debug")]
+    {
+        assert_eq!(format!("{:?}", E::Tuple()), "Tuple");
+        assert_eq!(format!("{:#?}", E::Named {}), "Named");
+    }
+    // The count still tells that there are no fields.
+    assert_eq!(format!("{:?}", Tuple()), "Tuple(..0)");
+    assert_eq!(format!("{:?}", Named {}), "Named");
+}
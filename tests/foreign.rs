@@ -0,0 +1,150 @@
+#![allow(dead_code)]
+
+mod external {
+    pub enum ExternalEnum {
+        VariantA,
+        VariantB(u8, u8),
+        VariantC { value: u8 },
+    }
+
+    pub struct NotDebug;
+
+    pub enum Other {
+        A(NotDebug),
+        B,
+    }
+}
+
+shallow_debug::shallow_debug!(external::ExternalEnum { VariantA, VariantB(_, _), VariantC { .. } });
+
+use external::Other;
+
+shallow_debug::shallow_debug!(
+    #[shallow_debug(rename = "Renamed")]
+    Other {
+        #[shallow_debug(skip)]
+        A(..),
+        B,
+    }
+);
+
+mod nested {
+    pub mod module {
+        pub enum Nested {
+            A(u8),
+            B,
+        }
+    }
+}
+
+// The path given to the macro is only used to name the type, the printed path is chosen with a
+// `rename`.
+shallow_debug::shallow_debug!(
+    #[shallow_debug(rename = "nested::module::Nested")]
+    nested::module::Nested { A(..), B }
+);
+
+#[test]
+fn foreign_enum() {
+    use external::ExternalEnum;
+
+    assert_eq!(format!("{:?}", ExternalEnum::VariantA), "ExternalEnum::VariantA");
+    assert_eq!(format!("{:?}", ExternalEnum::VariantB(1, 2)), "ExternalEnum::VariantB(..)");
+    assert_eq!(format!("{:?}", ExternalEnum::VariantC { value: 1 }), "ExternalEnum::VariantC{..}");
+}
+
+#[test]
+fn foreign_enum_attributes() {
+    assert_eq!(format!("{:?}", Other::A(external::NotDebug)), "Renamed::..");
+    assert_eq!(format!("{:?}", Other::B), "Renamed::B");
+}
+
+#[test]
+fn foreign_enum_path() {
+    use nested::module::Nested;
+
+    assert_eq!(format!("{:?}", Nested::A(1)), "nested::module::Nested::A(..)");
+    assert_eq!(format!("{:?}", Nested::B), "nested::module::Nested::B");
+}
+
+mod aliased {
+    pub enum Message<T> {
+        Ping,
+        Data(T),
+        Close { code: u16 },
+    }
+
+    pub type Request = Message<u8>;
+}
+
+use aliased::Request;
+
+shallow_debug::shallow_debug_alias!(
+    #[shallow_debug(name_fn)]
+    Request => aliased::Message {
+        Ping,
+        #[shallow_debug(rename = "Payload")]
+        Data(..),
+        Close { .. },
+    }
+);
+
+#[test]
+fn foreign_alias() {
+    assert_eq!(format!("{:?}", Request::Ping), "Request::Ping");
+    assert_eq!(format!("{:?}", Request::Data(1)), "Request::Payload(..)");
+    assert_eq!(format!("{:?}", aliased::Message::<u8>::Close { code: 1 }), "Request::Close{..}");
+    assert_eq!(Request::Data(1).shallow_name(), "Payload");
+}
+
+mod wrapped {
+    pub enum Status {
+        Up,
+        Down(u8),
+    }
+
+    pub enum State {
+        Idle,
+        Busy { job: u8 },
+    }
+}
+
+// Paths and identifiers passed through `macro_rules!` reach the macro as fragments.
+macro_rules! shallow_debug_path {
+    ($ty:path { $($variants:tt)* }) => {
+        shallow_debug::shallow_debug!($ty { $($variants)* });
+    };
+}
+
+macro_rules! shallow_debug_ident {
+    ($ty:ident { $($variants:tt)* }) => {
+        shallow_debug::shallow_debug!(wrapped::$ty { $($variants)* });
+    };
+}
+
+shallow_debug_path!(wrapped::Status { Up, Down(..) });
+shallow_debug_ident!(State { Idle, Busy { .. } });
+
+#[test]
+fn foreign_through_macros() {
+    assert_eq!(format!("{:?}", wrapped::Status::Down(1)), "Status::Down(..)");
+    assert_eq!(format!("{:?}", wrapped::State::Busy { job: 1 }), "State::Busy{..}");
+}
+
+mod suffixed {
+    pub enum Signal {
+        Stop,
+        Value(u8),
+    }
+}
+
+shallow_debug::shallow_debug!(
+    #[shallow_debug(unit_suffix = "()")]
+    suffixed::Signal { Stop, Value(..) }
+);
+
+#[test]
+fn foreign_unit_suffix() {
+    assert_eq!(format!("{:?}", suffixed::Signal::Stop), "Signal::Stop()");
+    assert_eq!(format!("{:?}", suffixed::Signal::Value(1)), "Signal::Value(..)");
+}
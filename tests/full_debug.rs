@@ -0,0 +1,70 @@
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug)]
+enum Shape {
+    Circle(f32),
+    Rect { w: u8, h: u8 },
+    Empty,
+}
+
+mod other {
+    // The feature isn't declared by this crate, which is exactly the case in which it is disabled.
+    #![allow(unexpected_cfgs)]
+
+    use shallow_debug::ShallowDebug;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(full_debug = "some-other-feature")]
+    pub struct Other(pub u8);
+}
+
+#[cfg(not(feature = "full-debug"))]
+#[test]
+fn shallow_without_feature() {
+    assert_eq!(format!("{:?}", Point { x: 1, y: 2 }), "Point{..}");
+    assert_eq!(format!("{:?}", Shape::Circle(1.0)), "Shape::Circle(..)");
+    assert_eq!(format!("{:?}", Shape::Rect { w: 1, h: 2 }), "Shape::Rect{..}");
+    assert_eq!(format!("{:?}", Shape::Empty), "Shape::Empty");
+}
+
+#[cfg(feature = "full-debug")]
+#[test]
+fn full_with_feature() {
+    assert_eq!(format!("{:?}", Point { x: 1, y: 2 }), "Point { x: 1, y: 2 }");
+    assert_eq!(format!("{:?}", Shape::Circle(1.0)), "Circle(1.0)");
+    assert_eq!(format!("{:?}", Shape::Rect { w: 1, h: 2 }), "Rect { w: 1, h: 2 }");
+    assert_eq!(format!("{:?}", Shape::Empty), "Empty");
+}
+
+#[test]
+fn other_feature() {
+    assert_eq!(format!("{:?}", other::Other(1)), "Other(..)");
+}
+
+struct NotDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug, no_bounds)]
+struct Id<T> {
+    id: u32,
+    marker: std::marker::PhantomData<T>,
+}
+
+#[test]
+fn no_bounds() {
+    let id = Id::<NotDebug> { id: 1, marker: std::marker::PhantomData };
+    #[cfg(feature = "full-debug")]
+    assert_eq!(format!("{id:?}"), "Id { id: 1, marker: PhantomData<full_debug::NotDebug> }");
+    #[cfg(not(feature = "full-debug"))]
+    assert_eq!(format!("{id:?}"), "Id{..}");
+}
@@ -0,0 +1,414 @@
+// The derived impls never read any fields, which is the whole point.
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[test]
+fn defaulted_type_param() {
+    #[derive(ShallowDebug)]
+    struct Foo<T = i32>(T);
+
+    let value: Foo = Foo(123);
+    assert_eq!(format!("{value:?}"), "Foo(..)");
+}
+
+#[test]
+fn defaulted_const_param() {
+    #[derive(ShallowDebug)]
+    struct Buf<const N: usize = 4>([u8; N]);
+
+    let value: Buf = Buf([0; 4]);
+    assert_eq!(format!("{value:?}"), "Buf(..)");
+}
+
+#[test]
+fn multiple_const_params() {
+    #[derive(ShallowDebug)]
+    struct Matrix<const R: usize, const C: usize>([[f64; C]; R]);
+
+    // Params of different types, which only compiles if they are passed in declaration order.
+    #[derive(ShallowDebug)]
+    struct Grid<const W: usize, const WRAP: bool, const H: usize> {
+        cells: [[u8; H]; W],
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(array_len)]
+    struct Rows<const R: usize, const C: usize>([[u8; C]; R]);
+
+    assert_eq!(format!("{:?}", Matrix([[0.0; 3]; 2])), "Matrix(..)");
+    let grid = Grid::<2, true, 3> { cells: [[0; 3]; 2] };
+    assert_eq!(format!("{grid:?}"), "Grid{..}");
+    assert_eq!(format!("{:?}", Rows([[0; 3]; 2])), "Rows([..; 2])");
+}
+
+#[test]
+fn const_lifetime_and_type_params() {
+    #[derive(ShallowDebug)]
+    enum Mixed<'a, T, const N: usize> {
+        Borrowed(&'a [T; N]),
+        Owned([T; N]),
+    }
+
+    let array = [1, 2, 3];
+    let borrowed = Mixed::Borrowed(&array);
+    let owned: Mixed<'_, i32, 3> = Mixed::Owned(array);
+    assert_eq!(format!("{borrowed:?}"), "Mixed::Borrowed(..)");
+    assert_eq!(format!("{owned:?}"), "Mixed::Owned(..)");
+}
+
+#[test]
+fn unbounded_type_param() {
+    #[derive(ShallowDebug)]
+    enum E<T> {
+        A(T),
+    }
+
+    assert_eq!(format!("{:?}", E::A(1)), "E::A(..)");
+}
+
+#[test]
+fn bound_override() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound = "T: Copy")]
+    struct Wrapper<T>(T);
+
+    fn assert_debug<T: std::fmt::Debug>(_: &T) {}
+
+    let value = Wrapper(1);
+    assert_debug(&value);
+    assert_eq!(format!("{value:?}"), "Wrapper(..)");
+}
+
+mod declares_trait {
+    use shallow_debug::ShallowDebug;
+
+    pub trait SomeTrait {}
+
+    impl SomeTrait for u8 {}
+
+    #[derive(ShallowDebug)]
+    pub enum E<T: SomeTrait> {
+        A(T),
+    }
+}
+
+#[test]
+fn bounds_not_in_scope() {
+    // Neither `SomeTrait` nor `Debug` are required of `u8` by the caller.
+    let value = declares_trait::E::A(1u8);
+    assert_eq!(format!("{value:?}"), "E::A(..)");
+}
+
+#[test]
+fn no_debug_bound_added() {
+    struct NotDebug;
+
+    #[derive(ShallowDebug)]
+    enum E<T: Sized> {
+        A(T),
+    }
+
+    assert_eq!(format!("{:?}", E::A(NotDebug)), "E::A(..)");
+}
+
+#[test]
+fn where_clause_without_generics() {
+    #[derive(ShallowDebug)]
+    struct Foo(u8)
+    where
+        u8: Copy;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound = "u8: Copy")]
+    enum Bar {
+        A,
+    }
+
+    assert_eq!(format!("{:?}", Foo(1)), "Foo(..)");
+    assert_eq!(format!("{:?}", Bar::A), "Bar::A");
+}
+
+#[test]
+fn overlapping_bounds() {
+    #[derive(ShallowDebug)]
+    enum E<'a, 'b: 'a, T: Clone + Send>
+    where
+        T: Clone,
+        'b: 'a,
+        T: 'a,
+    {
+        A(&'a T, &'b T),
+    }
+
+    let value = 1;
+    assert_eq!(format!("{:?}", E::A(&value, &value)), "E::A(..)");
+}
+
+#[test]
+fn lifetimes_only() {
+    #[derive(ShallowDebug)]
+    struct Ref<'a>(&'a str);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_fn)]
+    enum Borrowed<'a, 'b: 'a> {
+        Short(&'a str),
+        Long { value: &'b str },
+    }
+
+    fn format_ref<'a>(value: Ref<'a>) -> String {
+        format!("{value:?}")
+    }
+
+    let owned = String::from("temporary");
+    assert_eq!(format_ref(Ref(&owned)), "Ref(..)");
+    assert_eq!(format!("{:?}", Borrowed::Short(&owned)), "Borrowed::Short(..)");
+    assert_eq!(format!("{:?}", Borrowed::Long { value: "static" }), "Borrowed::Long{..}");
+    assert_eq!(Borrowed::Long { value: &owned }.shallow_name(), "Long");
+}
+
+#[test]
+fn unsized_params() {
+    #[derive(ShallowDebug)]
+    struct Wrapper<T: ?Sized>(Box<T>);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_fn)]
+    enum Mixed<'a, T: ?Sized + 'a, U: Send>
+    where
+        U: ?Sized,
+    {
+        Borrowed(&'a T),
+        Boxed(Box<U>),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound = "T: Send")]
+    struct Overridden<T: ?Sized + Send>(Box<T>);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound = "T: ?Sized")]
+    struct Repeated<T: ?Sized>(Box<T>);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent)]
+    struct Transparent<T: ?Sized>(Box<T>);
+
+    let text: &str = "text";
+    assert_eq!(format!("{:?}", Wrapper::<str>(text.into())), "Wrapper(..)");
+    assert_eq!(format!("{:?}", Mixed::<str, [u8]>::Borrowed(text)), "Mixed::Borrowed(..)");
+    assert_eq!(Mixed::<str, [u8]>::Borrowed(text).shallow_name(), "Borrowed");
+    assert_eq!(format!("{:?}", Overridden::<str>(text.into())), "Overridden(..)");
+    assert_eq!(format!("{:?}", Repeated::<str>(text.into())), "Repeated(..)");
+    assert_eq!(format!("{:?}", Transparent::<str>(text.into())), "\"text\"");
+}
+
+#[test]
+fn inline_generics() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics)]
+    enum MyEnum<'a, A, B: ?Sized, const N: usize> {
+        A(&'a A),
+        B {
+            #[shallow_debug(show)]
+            len: usize,
+            value: Box<B>,
+        },
+        C,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics, builder, prefix = "net")]
+    struct Wrapper<T>(T);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics)]
+    struct Plain {
+        a: u8,
+    }
+
+    type E<'a> = MyEnum<'a, i32, str, 3>;
+    assert_eq!(format!("{:?}", E::A(&1)), "MyEnum::<i32, str>::A(..)");
+    assert_eq!(
+        format!("{:?}", E::B { len: 2, value: "ab".into() }),
+        "MyEnum::<i32, str>::B { len: 2, .. }",
+    );
+    assert_eq!(format!("{:?}", E::C), "MyEnum::<i32, str>::C");
+    assert_eq!(format!("{:?}", Wrapper(1u8)), "net::Wrapper::<u8>(..)");
+    assert_eq!(format!("{:#?}", Wrapper(1u8)), "net::Wrapper::<u8>(\n    ..,\n)");
+    assert!(format!("{:?}", Wrapper(String::new())).contains("::String>("));
+    assert_eq!(format!("{:?}", Plain { a: 1 }), "Plain{..}");
+}
+
+#[test]
+fn no_bounds() {
+    use std::fmt;
+
+    // The bound that `transparent` adds isn't needed when the field is `Debug` for every `T`.
+    struct Inner<T>(T);
+
+    impl<T> fmt::Debug for Inner<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("Inner")
+        }
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent, no_bounds)]
+    struct Wrapper<T: Clone>(Inner<T>);
+
+    assert_eq!(format!("{:?}", Wrapper(Inner(1))), "Inner");
+}
+
+#[test]
+fn show_defaults() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_defaults)]
+    struct Foo<T = i32>(T);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_defaults, field_names)]
+    enum Cache<'a, K, V = Vec<K>, const N: usize = 4> {
+        Hit { key: &'a K, value: V },
+        Miss,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(show_defaults)]
+    struct NoDefaults<T>(T);
+
+    // The defaults are printed even when other arguments are used, so they are marked as such.
+    assert_eq!(format!("{:?}", Foo(1)), "Foo::</*defaults*/ T = i32>(..)");
+    assert_eq!(format!("{:?}", Foo("a")), "Foo::</*defaults*/ T = i32>(..)");
+    let hit: Cache<u8> = Cache::Hit { key: &1, value: vec![] };
+    assert_eq!(
+        format!("{hit:?}"),
+        "Cache::</*defaults*/ V = alloc::vec::Vec<u8>>::Hit { key, value, .. }",
+    );
+    let miss = Cache::<u8, String, 2>::Miss;
+    assert_eq!(format!("{miss:?}"), "Cache::</*defaults*/ V = alloc::vec::Vec<u8>>::Miss");
+    assert_eq!(format!("{:?}", NoDefaults(1)), "NoDefaults(..)");
+}
+
+#[test]
+fn bound_extra() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound_extra = "T: 'static")]
+    struct Handle<T: Send>(T);
+
+    fn assert_debug<T: std::fmt::Debug>(_: &T) {}
+
+    let value = Handle(1);
+    assert_debug(&value);
+    assert_eq!(format!("{value:?}"), "Handle(..)");
+}
+
+#[test]
+fn self_bounds() {
+    // `Self` in the forwarded predicates means the type the impl is for, as in the declaration.
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_fn, to_string_fn)]
+    enum Bounded<T>
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        A(T),
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(transparent)]
+    struct Wrapper<T>(T)
+    where
+        Self: Send;
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound = "Self: Sync", full_debug)]
+    struct Replaced<T>(T);
+
+    fn assert_debug<T: std::fmt::Debug>(_: &T) {}
+
+    assert_eq!(format!("{:?}", Bounded::A(1)), "Bounded::A(..)");
+    assert_eq!(Bounded::A(1).shallow_name(), "A");
+    assert_eq!(Bounded::A(1).shallow_to_string(), "Bounded::A(..)");
+    assert_eq!(format!("{:?}", Wrapper(1)), "1");
+    assert_debug(&Replaced(1));
+}
+
+#[test]
+fn inline_generics_in_field_types() {
+    // The type arguments are written once, before matching on the variant, whatever the fields
+    // do with the parameters.
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics)]
+    enum Transform<T> {
+        Values(Vec<T>),
+        Map(fn(T) -> T),
+        Nested { inner: Option<Box<Transform<T>>> },
+    }
+
+    fn double(x: u16) -> u16 {
+        x * 2
+    }
+
+    let outputs = [
+        format!("{:?}", Transform::Values(vec![1u16])),
+        format!("{:?}", Transform::Map(double)),
+        format!("{:?}", Transform::<u16>::Nested { inner: None }),
+    ];
+    assert_eq!(outputs[0], "Transform::<u16>::Values(..)");
+    assert_eq!(outputs[1], "Transform::<u16>::Map(..)");
+    assert_eq!(outputs[2], "Transform::<u16>::Nested{..}");
+    for output in &outputs {
+        assert_eq!(output.matches("u16").count(), 1, "{output}");
+    }
+}
+
+#[test]
+fn param_bound() {
+    struct NotDebug;
+
+    #[derive(ShallowDebug)]
+    enum Event<#[shallow_debug(bound)] T, U, const N: usize> {
+        Key(#[shallow_debug(show)] T, U),
+        Batch {
+            #[shallow_debug(show)]
+            first: Option<T>,
+            rest: [U; N],
+        },
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(bound_extra = "U: Copy")]
+    struct Pair<'a, #[shallow_debug(bound)] T: Clone, U>(#[shallow_debug(show)] &'a T, U);
+
+    fn assert_debug<T: std::fmt::Debug>(_: &T) {}
+
+    let key = Event::<_, _, 0>::Key('a', NotDebug);
+    assert_eq!(format!("{key:?}"), "Event::Key('a', ..)");
+    let batch = Event::Batch { first: Some(1), rest: [NotDebug] };
+    assert_eq!(format!("{batch:?}"), "Event::Batch { first: Some(1), .. }");
+    let pair = Pair(&"a", 1);
+    assert_debug(&pair);
+    assert_eq!(format!("{pair:?}"), "Pair(\"a\", ..)");
+}
+
+#[test]
+fn inline_generics_shown_tuple() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics)]
+    struct Single<#[shallow_debug(bound)] T>(#[shallow_debug(show)] T);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(inline_generics)]
+    enum Wrapped<#[shallow_debug(bound)] T> {
+        One(#[shallow_debug(show)] T),
+        Two(#[shallow_debug(show)] T, T),
+    }
+
+    assert_eq!(format!("{:?}", Single(1)), "Single::<i32>(1)");
+    assert_eq!(format!("{:#?}", Single(1)), "Single::<i32>(\n    1,\n)");
+    assert_eq!(format!("{:?}", Wrapped::One(1u8)), "Wrapped::<u8>::One(1)");
+    assert_eq!(format!("{:?}", Wrapped::Two(1u8, 2)), "Wrapped::<u8>::Two(1, ..)");
+}
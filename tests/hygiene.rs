@@ -0,0 +1,161 @@
+#![allow(dead_code, non_upper_case_globals)]
+
+use shallow_debug::ShallowDebug;
+
+mod constants {
+    use shallow_debug::ShallowDebug;
+
+    // Constants are in scope in the generated code, unlike local variables.
+    pub const f: u8 = 0;
+    pub const fmt: u8 = 0;
+
+    #[derive(ShallowDebug)]
+    pub enum Shadowed {
+        A(u8),
+        B {
+            #[shallow_debug(show)]
+            id: u8,
+        },
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(builder)]
+    pub struct Builder {
+        pub a: u8,
+    }
+}
+
+#[test]
+fn local_formatter_name() {
+    let f = 1;
+    let __formatter = 2;
+
+    #[derive(ShallowDebug)]
+    struct Local(u8);
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(field_names)]
+    struct Fields {
+        f: u8,
+    }
+
+    assert_eq!(f + __formatter, 3);
+    assert_eq!(format!("{:?}", Local(1)), "Local(..)");
+    assert_eq!(format!("{:#?}", Fields { f: 1 }), "Fields {\n    f,\n    ..\n}");
+}
+
+#[test]
+fn constants_in_scope() {
+    use constants::{Builder, Shadowed};
+
+    assert_eq!(format!("{:?}", Shadowed::A(1)), "Shadowed::A(..)");
+    assert_eq!(format!("{:?}", Shadowed::B { id: 7 }), "Shadowed::B { id: 7 }");
+    assert_eq!(format!("{:?}", Builder { a: 1 }), "Builder { .. }");
+}
+
+mod outer {
+    pub mod inner {
+        use shallow_debug::ShallowDebug;
+
+        #[derive(ShallowDebug)]
+        #[shallow_debug(name_fn)]
+        pub enum State {
+            Idle,
+            Busy(u8),
+        }
+    }
+}
+
+struct Holder<T>(T);
+
+impl<T> Holder<T> {
+    // The derive only sees the item, so a local one is named by its identifier like any other,
+    // and the generic parameters of the surrounding impl aren't in scope for it.
+    fn describe(&self) -> &'static str {
+        #[derive(ShallowDebug)]
+        #[shallow_debug(to_string_fn, same_variant)]
+        enum Local {
+            Empty,
+            Full { len: usize },
+        }
+
+        Local::Full { len: 1 }.shallow_to_string()
+    }
+}
+
+trait Describe {
+    fn describe() -> String {
+        // Shadows the struct of the same name at the root of the crate, which isn't an issue either.
+        #[derive(ShallowDebug)]
+        enum Holder {
+            A(u8),
+        }
+
+        format!("{:?}", Holder::A(1))
+    }
+}
+
+impl Describe for u8 {}
+
+#[test]
+fn item_positions() {
+    assert_eq!(format!("{:?}", outer::inner::State::Busy(1)), "State::Busy(..)");
+    assert_eq!(outer::inner::State::Idle.shallow_name(), "Idle");
+    assert_eq!(Holder(0u8).describe(), "Local::Full{..}");
+    assert_eq!(<u8 as Describe>::describe(), "Holder::A(..)");
+}
+
+#[test]
+fn variant_named_like_type() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(name_fn, variants_const)]
+    enum Json {
+        Json(String),
+        Null,
+    }
+
+    #[derive(ShallowDebug)]
+    #[shallow_debug(variant_only, to_string_fn)]
+    enum Only {
+        Only { value: u8 },
+        Other,
+    }
+
+    assert_eq!(format!("{:?}", Json::Json("{}".into())), "Json::Json(..)");
+    assert_eq!(format!("{:?}", Json::Null), "Json::Null");
+    assert_eq!(Json::Json(String::new()).shallow_name(), "Json");
+    assert_eq!(Json::VARIANTS, ["Json", "Null"]);
+    assert_eq!(format!("{:?}", Only::Only { value: 1 }), "Only{..}");
+    assert_eq!(Only::Only { value: 1 }.shallow_to_string(), "Only{..}");
+    assert_eq!(format!("{:?}", Only::Other), "Other");
+}
+
+mod scoped {
+    use shallow_debug::{ShallowDebug, ShallowDisplay};
+
+    // Would clash with any item the derives add to this module, like `extern crate alloc`.
+    pub mod alloc {}
+    pub struct String;
+
+    #[derive(ShallowDebug, ShallowDisplay)]
+    #[shallow_debug(to_string_fn, write_fn, name_fn)]
+    pub struct Helpers {
+        #[shallow_debug(show)]
+        pub id: u8,
+        pub data: u8,
+    }
+}
+
+#[test]
+fn no_leaked_items() {
+    use scoped::*;
+
+    let helpers = Helpers { id: 1, data: 2 };
+    assert_eq!(format!("{helpers:?}"), "Helpers { id: 1, .. }");
+    assert_eq!(format!("{helpers}"), "Helpers { id: 1, .. }");
+    assert_eq!(helpers.shallow_to_string(), "Helpers { id: 1, .. }");
+    let mut output = std::string::String::new();
+    helpers.shallow_fmt(&mut output).unwrap();
+    assert_eq!(output, "Helpers { id: 1, .. }");
+    assert_eq!(helpers.shallow_name(), "Helpers");
+}
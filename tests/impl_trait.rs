@@ -0,0 +1,153 @@
+#![allow(dead_code, non_camel_case_types)]
+
+use shallow_debug::ShallowDebug;
+
+/// The parts of the `ufmt` API used by the generated impls, with the same signatures.
+mod ufmt {
+    pub trait uWrite {
+        type Error;
+
+        fn write_str(&mut self, s: &str) -> Result<(), Self::Error>;
+    }
+
+    pub struct Formatter<'w, W: uWrite + ?Sized> {
+        writer: &'w mut W,
+    }
+
+    impl<'w, W: uWrite + ?Sized> Formatter<'w, W> {
+        pub fn new(writer: &'w mut W) -> Self {
+            Formatter { writer }
+        }
+
+        pub fn write_str(&mut self, s: &str) -> Result<(), W::Error> {
+            self.writer.write_str(s)
+        }
+    }
+
+    pub trait uDebug {
+        fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+        where
+            W: uWrite + ?Sized;
+    }
+
+    pub trait uDisplay {
+        fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+        where
+            W: uWrite + ?Sized;
+    }
+
+    impl uWrite for String {
+        type Error = core::convert::Infallible;
+
+        fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+            self.push_str(s);
+            Ok(())
+        }
+    }
+
+    /// What `ufmt::uwrite!(s, "{:?}", value)` would write.
+    pub fn to_string(value: &impl uDebug) -> String {
+        let mut s = String::new();
+        value.fmt(&mut Formatter::new(&mut s)).unwrap();
+        s
+    }
+
+    /// What `ufmt::uwrite!(s, "{}", value)` would write.
+    pub fn to_display_string(value: &impl uDisplay) -> String {
+        let mut s = String::new();
+        value.fmt(&mut Formatter::new(&mut s)).unwrap();
+        s
+    }
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "ufmt::uDebug")]
+enum Event<T> {
+    Click { x: i32, y: i32 },
+    Key(char),
+    #[shallow_debug(rename = "Idle")]
+    Nothing,
+    #[shallow_debug(skip)]
+    Internal(T),
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "crate::ufmt::uDisplay", field_names)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "ufmt::uDebug", transparent)]
+struct Wrapper<T>(T);
+
+fn write_custom<W>(_: &Custom, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+where
+    W: ufmt::uWrite + ?Sized,
+{
+    f.write_str("custom")
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "ufmt::uDebug", with = write_custom)]
+struct Custom(u8);
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "core::fmt::Debug", prefix = "std")]
+struct Standard(u8);
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "ufmt::uDebug", variant_index)]
+enum Indexed {
+    A,
+    B(u8),
+}
+
+#[test]
+fn ufmt_debug() {
+    assert_eq!(ufmt::to_string(&Event::<u8>::Click { x: 1, y: 2 }), "Event::Click{..}");
+    assert_eq!(ufmt::to_string(&Event::<u8>::Key('a')), "Event::Key(..)");
+    assert_eq!(ufmt::to_string(&Event::<u8>::Nothing), "Event::Idle");
+    assert_eq!(ufmt::to_string(&Event::Internal(1)), "Event::..");
+}
+
+#[test]
+fn ufmt_variant_index() {
+    assert_eq!(ufmt::to_string(&Indexed::A), "Indexed::A [0/2]");
+    assert_eq!(ufmt::to_string(&Indexed::B(1)), "Indexed::B(..) [1/2]");
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(impl_trait = "ufmt::uDebug", separator = ".")]
+enum Separated {
+    A,
+    #[shallow_debug(skip)]
+    B,
+}
+
+#[test]
+fn ufmt_separator() {
+    assert_eq!(ufmt::to_string(&Separated::A), "Separated.A");
+    assert_eq!(ufmt::to_string(&Separated::B), "Separated::..");
+}
+
+#[test]
+fn ufmt_display() {
+    assert_eq!(ufmt::to_display_string(&Point { x: 1, y: 2 }), "Point { x, y, .. }");
+}
+
+#[test]
+fn ufmt_transparent() {
+    assert_eq!(ufmt::to_string(&Wrapper(Event::<u8>::Nothing)), "Event::Idle");
+}
+
+#[test]
+fn ufmt_with() {
+    assert_eq!(ufmt::to_string(&Custom(1)), "custom");
+}
+
+#[test]
+fn core_debug() {
+    assert_eq!(format!("{:?}", Standard(1)), "std::Standard(..)");
+}
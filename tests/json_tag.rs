@@ -0,0 +1,127 @@
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(json_tag)]
+enum Event {
+    Unit,
+    Tuple(u32, String),
+    Struct { id: u32 },
+    Empty(),
+    #[shallow_debug(rename = r#"say "hi""#)]
+    Quoted,
+    #[shallow_debug(rename = "back\\slash\nnew\tline\u{1}")]
+    Escaped,
+    #[shallow_debug(skip)]
+    Hidden(u32),
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(json_tag, variant_only, case = "snake")]
+enum Command {
+    GetUser { id: u32 },
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(json_tag, prefix = "net")]
+struct Conn {
+    #[shallow_debug(skip)]
+    secret: u64,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(json_tag)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+/// Parses a JSON object whose values are all strings, returning its entries, or panics if `json`
+/// isn't valid JSON of that shape.
+fn parse_object(json: &str) -> Vec<(String, String)> {
+    fn string(chars: &mut std::str::Chars) -> String {
+        assert_eq!(chars.next(), Some('"'));
+        let mut string = String::new();
+        loop {
+            match chars.next().expect("unterminated string") {
+                '"' => return string,
+                '\\' => match chars.next().expect("unterminated escape") {
+                    c @ ('"' | '\\' | '/') => string.push(c),
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'u' => {
+                        let hex = chars.take(4).collect::<String>();
+                        let code = u32::from_str_radix(&hex, 16).expect("invalid escape");
+                        string.push(char::from_u32(code).unwrap());
+                    }
+                    c => panic!("invalid escape `\\{c}`"),
+                },
+                c if (c as u32) < 0x20 => panic!("unescaped control character {c:?}"),
+                c => string.push(c),
+            }
+        }
+    }
+
+    let mut chars = json.chars();
+    assert_eq!(chars.next(), Some('{'));
+    let mut entries = Vec::new();
+    loop {
+        let key = string(&mut chars);
+        assert_eq!(chars.next(), Some(':'));
+        entries.push((key, string(&mut chars)));
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => break,
+            c => panic!("expected `,` or `}}`, found {c:?}"),
+        }
+    }
+    assert_eq!(chars.next(), None);
+    entries
+}
+
+fn tag(json: &str) -> String {
+    let entries = parse_object(json);
+    assert_eq!(entries.len(), 1, "{json}");
+    assert_eq!(entries[0].0, "type");
+    entries[0].1.clone()
+}
+
+#[test]
+fn variant_kinds() {
+    let cases = [
+        (Event::Unit, "Event::Unit"),
+        (Event::Tuple(1, "secret".into()), "Event::Tuple"),
+        (Event::Struct { id: 1 }, "Event::Struct"),
+        (Event::Empty(), "Event::Empty"),
+        (Event::Hidden(1), "Event::.."),
+    ];
+    for (value, name) in cases {
+        let json = format!("{value:?}");
+        assert_eq!(json, format!(r#"{{"type":"{name}"}}"#));
+        assert_eq!(tag(&json), name);
+        // The alternate mode prints the same object.
+        assert_eq!(format!("{value:#?}"), json);
+    }
+}
+
+#[test]
+fn escaping() {
+    let json = format!("{:?}", Event::Quoted);
+    assert_eq!(json, r#"{"type":"Event::say \"hi\""}"#);
+    assert_eq!(tag(&json), r#"Event::say "hi""#);
+
+    let json = format!("{:?}", Event::Escaped);
+    assert_eq!(json, r#"{"type":"Event::back\\slash\nnew\tline\u0001"}"#);
+    assert_eq!(tag(&json), "Event::back\\slash\nnew\tline\u{1}");
+}
+
+#[test]
+fn other_items() {
+    assert_eq!(format!("{:?}", Command::GetUser { id: 1 }), r#"{"type":"get_user"}"#);
+    assert_eq!(format!("{:?}", Conn { secret: 1 }), r#"{"type":"net::Conn"}"#);
+    assert_eq!(format!("{:?}", Bits { int: 1 }), r#"{"type":"Bits"}"#);
+}
@@ -0,0 +1,64 @@
+#![deny(warnings, unused_qualifications)]
+#![allow(dead_code)]
+
+use std::fmt::{Debug, Formatter, Result};
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+struct Qualified(u8);
+
+#[derive(ShallowDebug)]
+enum GenericQualified<T> {
+    A(T),
+}
+
+// Only the shown fields are bound by the patterns of the generated `match`, so that none of them
+// is unused, and skipped variants bind nothing.
+#[derive(ShallowDebug)]
+#[shallow_debug(full_debug, to_string_fn)]
+enum Shown<T> {
+    Tuple(#[shallow_debug(show)] u8, T),
+    Named {
+        #[shallow_debug(show)]
+        id: u8,
+        value: T,
+    },
+    #[shallow_debug(skip)]
+    Skipped(#[shallow_debug(show)] u8),
+    Elided(T),
+    Unit,
+}
+
+#[derive(ShallowDebug)]
+struct Skipping {
+    #[shallow_debug(skip)]
+    secret: u64,
+    #[cfg_attr(all(), shallow_debug(show))]
+    id: u8,
+    #[cfg(any())]
+    removed: u8,
+}
+
+#[derive(ShallowDebug)]
+enum Empty {}
+
+// Make sure the imports above are used, so that only the derived code may trigger the lint.
+fn uses_imports(value: &dyn Debug, f: &mut Formatter<'_>) -> Result {
+    value.fmt(f)
+}
+
+#[test]
+fn fully_qualified_paths_are_allowed() {
+    assert_eq!(format!("{:?}", Qualified(1)), "Qualified(..)");
+    assert_eq!(format!("{:?}", GenericQualified::A(1)), "GenericQualified::A(..)");
+}
+
+#[test]
+#[cfg(not(feature = "full-debug"))]
+fn shown_fields_without_warnings() {
+    assert_eq!(format!("{:?}", Shown::Tuple(1, ())), "Shown::Tuple(1, ..)");
+    assert_eq!(format!("{:?}", Shown::Named { id: 1, value: () }), "Shown::Named { id: 1, .. }");
+    assert_eq!(format!("{:?}", Shown::<()>::Skipped(1)), "Shown::..");
+    assert_eq!(format!("{:?}", Skipping { secret: 1, id: 2 }), "Skipping { id: 2, .. }");
+}
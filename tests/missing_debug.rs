@@ -0,0 +1,57 @@
+// Public types without a `Debug` impl would fail to compile, so this only compiles if the derived
+// impls are recognized.
+#![deny(missing_debug_implementations)]
+#![allow(dead_code)]
+
+use shallow_debug::{Shallow, ShallowDebug};
+
+struct NotDebug;
+
+#[derive(ShallowDebug)]
+pub enum Event<T> {
+    Data(T),
+    Empty,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_fn, variants_const, same_variant, full_debug)]
+pub enum Full {
+    A(u8),
+}
+
+#[derive(ShallowDebug)]
+pub struct Handle {
+    raw: NotDebug,
+}
+
+#[derive(ShallowDebug)]
+pub union Raw {
+    int: u32,
+    float: f32,
+}
+
+pub mod generated {
+    pub enum Message {
+        Ping,
+    }
+}
+
+shallow_debug::shallow_debug!(generated::Message { Ping });
+
+pub struct Wrapped {
+    inner: Shallow<NotDebug>,
+}
+
+impl core::fmt::Debug for Wrapped {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Wrapped").field("inner", &self.inner).finish()
+    }
+}
+
+#[test]
+fn debug_implementations() {
+    assert_eq!(format!("{:?}", Handle { raw: NotDebug }), "Handle{..}");
+    assert_eq!(format!("{:?}", generated::Message::Ping), "Message::Ping");
+    let wrapped = Wrapped { inner: Shallow(NotDebug) };
+    assert_eq!(format!("{wrapped:?}"), "Wrapped { inner: Shallow(missing_debug::NotDebug) }");
+}
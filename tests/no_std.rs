@@ -0,0 +1,38 @@
+//! The derive must work in crates that only have access to `core`.
+#![no_std]
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+enum Message<T> {
+    Data(T),
+    Empty,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(to_string_fn)]
+struct Packet {
+    len: usize,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(to_string_fn)]
+struct Frame {
+    #[shallow_debug(show)]
+    len: usize,
+}
+
+#[test]
+fn derive_without_std() {
+    // Only bring in `alloc` here, so that the derived impls can't resolve anything from it.
+    extern crate alloc;
+    use alloc::format;
+
+    assert_eq!(format!("{:?}", Message::Data(1)), "Message::Data(..)");
+    assert_eq!(format!("{:?}", Message::<u8>::Empty), "Message::Empty");
+    assert_eq!(format!("{:?}", Packet { len: 0 }), "Packet{..}");
+    assert_eq!(Packet { len: 0 }.shallow_to_string(), "Packet{..}");
+    // A shown field is formatted into a `String` from `alloc`.
+    assert_eq!(Frame { len: 0 }.shallow_to_string(), "Frame { len: 0 }");
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+#![deny(unreachable_patterns)]
+
+use shallow_debug::ShallowDebug;
+
+#[derive(ShallowDebug)]
+#[shallow_debug(name_fn, full_debug)]
+#[non_exhaustive]
+enum Error {
+    NotFound,
+    #[non_exhaustive]
+    Io(u8),
+    #[non_exhaustive]
+    Parse { line: u32 },
+}
+
+#[derive(ShallowDebug)]
+#[non_exhaustive]
+struct Config {
+    verbose: bool,
+}
+
+#[test]
+fn non_exhaustive() {
+    assert_eq!(Error::Parse { line: 1 }.shallow_name(), "Parse");
+    assert_eq!(format!("{:?}", Config { verbose: true }), "Config{..}");
+}
+
+#[test]
+#[cfg(not(feature = "full-debug"))]
+fn non_exhaustive_shallow() {
+    assert_eq!(format!("{:?}", Error::NotFound), "Error::NotFound");
+    assert_eq!(format!("{:?}", Error::Io(1)), "Error::Io(..)");
+    assert_eq!(format!("{:?}", Error::Parse { line: 1 }), "Error::Parse{..}");
+}
+
+#[test]
+#[cfg(feature = "full-debug")]
+fn non_exhaustive_full() {
+    assert_eq!(format!("{:?}", Error::NotFound), "NotFound");
+    assert_eq!(format!("{:?}", Error::Io(1)), "Io(1)");
+    assert_eq!(format!("{:?}", Error::Parse { line: 1 }), "Parse { line: 1 }");
+}
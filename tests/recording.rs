@@ -0,0 +1,58 @@
+//! Structured loggers like `tracing` record `?field` values through `&dyn Debug`, and nest them
+//! inside their own output. This mimics such a recorder, without depending on one.
+#![allow(dead_code)]
+
+use std::fmt::{self, Debug, Write};
+
+use shallow_debug::ShallowDebug;
+
+/// Records fields as `name=value` pairs, the way `tracing-subscriber`'s default formatter does.
+#[derive(Default)]
+struct Recorder {
+    output: String,
+}
+
+impl Recorder {
+    fn record_debug(&mut self, name: &str, value: &dyn Debug) -> fmt::Result {
+        if !self.output.is_empty() {
+            self.output.push(' ');
+        }
+        write!(self.output, "{name}={value:?}")
+    }
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(builder)]
+enum Request {
+    Get { path: String },
+    Post(Vec<u8>),
+    Ping,
+}
+
+#[derive(ShallowDebug)]
+#[shallow_debug(builder)]
+struct Session {
+    #[shallow_debug(show)]
+    id: u32,
+    secret: String,
+}
+
+#[derive(Debug)]
+struct Event {
+    request: Request,
+}
+
+#[test]
+fn record_debug_fields() {
+    let mut recorder = Recorder::default();
+    recorder.record_debug("get", &Request::Get { path: "/".into() }).unwrap();
+    recorder.record_debug("post", &Request::Post(vec![1])).unwrap();
+    recorder.record_debug("ping", &Request::Ping).unwrap();
+    recorder.record_debug("session", &Session { id: 1, secret: "hunter2".into() }).unwrap();
+    recorder.record_debug("event", &Event { request: Request::Ping }).unwrap();
+    assert_eq!(
+        recorder.output,
+        "get=Request::Get { .. } post=Request::Post(..) ping=Request::Ping \
+         session=Session { id: 1, .. } event=Event { request: Request::Ping }",
+    );
+}
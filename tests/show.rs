@@ -0,0 +1,249 @@
+#![allow(dead_code)]
+
+use shallow_debug::ShallowDebug;
+
+struct NotDebug;
+
+#[derive(ShallowDebug)]
+struct Mixed {
+    #[shallow_debug(show)]
+    id: u32,
+    hidden: NotDebug,
+    #[shallow_debug(show)]
+    name: &'static str,
+}
+
+#[derive(ShallowDebug)]
+struct AllShown {
+    #[shallow_debug(show)]
+    id: u32,
+}
+
+#[derive(ShallowDebug)]
+enum Event {
+    Click {
+        #[shallow_debug(show)]
+        x: i32,
+        target: NotDebug,
+    },
+    Other(NotDebug),
+}
+
+#[test]
+fn show_struct_fields() {
+    let mixed = Mixed { id: 1, hidden: NotDebug, name: "one" };
+    assert_eq!(format!("{mixed:?}"), r#"Mixed { id: 1, name: "one", .. }"#);
+    assert_eq!(format!("{:?}", AllShown { id: 2 }), "AllShown { id: 2 }");
+}
+
+#[test]
+fn show_variant_fields() {
+    let click = Event::Click { x: 3, target: NotDebug };
+    assert_eq!(format!("{click:?}"), "Event::Click { x: 3, .. }");
+    assert_eq!(format!("{:?}", Event::Other(NotDebug)), "Event::Other(..)");
+}
+
+#[test]
+fn shown_fields_use_formatter_flags() {
+    #[derive(Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(ShallowDebug)]
+    struct Shape {
+        #[shallow_debug(show)]
+        origin: Point,
+        #[shallow_debug(show)]
+        scale: f32,
+        hidden: NotDebug,
+    }
+
+    let shape = Shape { origin: Point { x: 1, y: 2 }, scale: 1.5, hidden: NotDebug };
+    assert_eq!(
+        format!("{shape:#?}"),
+        "Shape {\n    origin: Point {\n        x: 1,\n        y: 2,\n    },\n    scale: 1.5,\n    ..\n}",
+    );
+    assert_eq!(
+        format!("{shape:.2?}"),
+        "Shape { origin: Point { x: 1, y: 2 }, scale: 1.50, .. }",
+    );
+    assert_eq!(
+        format!("{shape:3?}"),
+        "Shape { origin: Point { x:   1, y:   2 }, scale: 1.5, .. }",
+    );
+}
+
+#[test]
+fn skip_fields() {
+    #[derive(ShallowDebug)]
+    struct User {
+        id: u32,
+        name: &'static str,
+        #[shallow_debug(skip)]
+        password: NotDebug,
+    }
+
+    #[derive(ShallowDebug)]
+    enum Request {
+        Login {
+            user: &'static str,
+            #[shallow_debug(skip)]
+            token: NotDebug,
+        },
+        Logout,
+    }
+
+    let user = User { id: 1, name: "alice", password: NotDebug };
+    assert_eq!(format!("{user:?}"), r#"User { id: 1, name: "alice", .. }"#);
+    assert_eq!(format!("{user:#?}"), "User {\n    id: 1,\n    name: \"alice\",\n    ..\n}");
+    let login = Request::Login { user: "bob", token: NotDebug };
+    assert_eq!(format!("{login:?}"), r#"Request::Login { user: "bob", .. }"#);
+    assert_eq!(format!("{:?}", Request::Logout), "Request::Logout");
+}
+
+#[test]
+fn declaration_order() {
+    #[derive(ShallowDebug)]
+    struct Order {
+        #[shallow_debug(show)]
+        zeta: u8,
+        hidden: NotDebug,
+        #[shallow_debug(show)]
+        alpha: u8,
+        #[shallow_debug(show)]
+        mu: u8,
+    }
+
+    #[derive(ShallowDebug)]
+    enum Message {
+        Data {
+            #[shallow_debug(show)]
+            last: u8,
+            #[shallow_debug(show)]
+            first: u8,
+            payload: NotDebug,
+        },
+    }
+
+    let order = Order { zeta: 1, hidden: NotDebug, alpha: 2, mu: 3 };
+    assert_eq!(format!("{order:?}"), "Order { zeta: 1, alpha: 2, mu: 3, .. }");
+    let message = Message::Data { last: 1, first: 2, payload: NotDebug };
+    assert_eq!(format!("{message:?}"), "Message::Data { last: 1, first: 2, .. }");
+}
+
+#[derive(Debug)]
+enum Button {
+    Left,
+    Right,
+}
+
+#[derive(ShallowDebug)]
+enum Input {
+    Clicked(#[shallow_debug(show)] Button, NotDebug),
+    Moved(NotDebug, #[shallow_debug(show)] i32, NotDebug),
+    Scrolled(#[shallow_debug(show)] i32),
+    Other(NotDebug),
+}
+
+#[derive(ShallowDebug)]
+struct Pair(NotDebug, #[shallow_debug(show)] u8);
+
+#[test]
+fn show_positional_fields() {
+    let clicked = Input::Clicked(Button::Left, NotDebug);
+    assert_eq!(format!("{clicked:?}"), "Input::Clicked(Left, ..)");
+    assert_eq!(format!("{clicked:#?}"), "Input::Clicked(\n    Left,\n    ..,\n)");
+    let moved = Input::Moved(NotDebug, 3, NotDebug);
+    assert_eq!(format!("{moved:?}"), "Input::Moved(3, ..)");
+    assert_eq!(format!("{:?}", Input::Scrolled(-1)), "Input::Scrolled(-1)");
+    assert_eq!(format!("{:?}", Input::Other(NotDebug)), "Input::Other(..)");
+    assert_eq!(format!("{:?}", Pair(NotDebug, 2)), "Pair(2, ..)");
+}
+
+#[test]
+fn phantom_data() {
+    use std::marker::PhantomData;
+
+    #[derive(ShallowDebug)]
+    struct Typed<T> {
+        id: u32,
+        marker: PhantomData<T>,
+    }
+
+    #[derive(ShallowDebug)]
+    struct AllTyped<T> {
+        id: u32,
+        #[shallow_debug(skip)]
+        cache: NotDebug,
+        marker: PhantomData<T>,
+    }
+
+    #[derive(ShallowDebug)]
+    struct ShownMarker<T> {
+        id: u32,
+        #[shallow_debug(show)]
+        marker: PhantomData<T>,
+    }
+
+    #[derive(ShallowDebug)]
+    struct SkippedShownMarker<T> {
+        id: u32,
+        #[shallow_debug(skip)]
+        cache: NotDebug,
+        #[shallow_debug(show)]
+        marker: PhantomData<T>,
+    }
+
+    // No `T: Debug` bound is needed in any mode.
+    let typed = Typed::<NotDebug> { id: 1, marker: PhantomData };
+    assert_eq!(format!("{typed:?}"), "Typed{..}");
+    let all = AllTyped::<NotDebug> { id: 1, cache: NotDebug, marker: PhantomData };
+    assert_eq!(format!("{all:?}"), "AllTyped { id: 1, .. }");
+    let shown = ShownMarker::<u8> { id: 1, marker: PhantomData };
+    assert_eq!(format!("{shown:?}"), "ShownMarker { marker: PhantomData<u8>, .. }");
+    let skipped = SkippedShownMarker::<u8> { id: 1, cache: NotDebug, marker: PhantomData };
+    assert_eq!(
+        format!("{skipped:?}"),
+        "SkippedShownMarker { id: 1, marker: PhantomData<u8>, .. }",
+    );
+}
+
+#[test]
+fn mutable_references() {
+    #[derive(ShallowDebug)]
+    #[shallow_debug(full_debug, name_fn)]
+    enum Borrowed<'a, T> {
+        Hidden(&'a mut T),
+        Named {
+            #[shallow_debug(show)]
+            value: &'a mut u32,
+            owner: String,
+        },
+        Positional(#[shallow_debug(show)] &'a mut String, Vec<T>),
+    }
+
+    let mut value = 1;
+    let mut text = String::from("text");
+    let mut hidden = 2u8;
+    let named: Borrowed<u8> = Borrowed::Named { value: &mut value, owner: "me".into() };
+    let positional: Borrowed<u8> = Borrowed::Positional(&mut text, Vec::new());
+    let hidden = Borrowed::Hidden(&mut hidden);
+    // The fields are only borrowed through `&self`, so the values can be formatted again.
+    for _ in 0..2 {
+        #[cfg(not(feature = "full-debug"))]
+        {
+            assert_eq!(format!("{named:?}"), "Borrowed::Named { value: 1, .. }");
+            assert_eq!(format!("{positional:?}"), r#"Borrowed::Positional("text", ..)"#);
+            assert_eq!(format!("{hidden:?}"), "Borrowed::Hidden(..)");
+        }
+        #[cfg(feature = "full-debug")]
+        {
+            assert_eq!(format!("{named:?}"), r#"Named { value: 1, owner: "me" }"#);
+            assert_eq!(format!("{positional:?}"), r#"Positional("text", [])"#);
+            assert_eq!(format!("{hidden:?}"), "Hidden(2)");
+        }
+    }
+    assert_eq!(hidden.shallow_name(), "Hidden");
+}
@@ -0,0 +1,22 @@
+use shallow_debug::Shallow;
+
+mod module {
+    pub struct Thing;
+}
+
+#[test]
+fn owned() {
+    assert_eq!(format!("{:?}", Shallow(module::Thing)), "Shallow(wrapper::module::Thing)");
+    assert_eq!(format!("{:?}", Shallow(1u8)), "Shallow(u8)");
+}
+
+#[test]
+fn reference() {
+    let thing = module::Thing;
+    assert_eq!(format!("{:?}", Shallow(&thing)), "Shallow(&wrapper::module::Thing)");
+}
+
+#[test]
+fn alternate() {
+    assert_eq!(format!("{:#?}", Shallow(1u8)), "Shallow(\n    u8,\n)");
+}